@@ -0,0 +1,141 @@
+#[macro_use]
+extern crate criterion;
+#[macro_use]
+extern crate glib;
+
+use criterion::{black_box, Criterion};
+use glib::prelude::*;
+use glib::subclass;
+use glib::subclass::prelude::*;
+use glib::{Object, SignalFlags, Value};
+
+use std::cell::Cell;
+
+static PROPERTIES: [subclass::Property; 1] = [subclass::Property("number", |name| {
+    glib::ParamSpec::int(
+        name,
+        "Number",
+        "Some number",
+        i32::min_value(),
+        i32::max_value(),
+        0,
+        glib::ParamFlags::READWRITE,
+    )
+})];
+
+pub struct BenchObject {
+    number: Cell<i32>,
+}
+
+impl ObjectSubclass for BenchObject {
+    const NAME: &'static str = "BenchObject";
+    type ParentType = Object;
+    type Instance = subclass::simple::InstanceStruct<Self>;
+    type Class = subclass::simple::ClassStruct<Self>;
+
+    glib_object_subclass!();
+
+    fn class_init(klass: &mut subclass::simple::ClassStruct<Self>) {
+        klass.install_properties(&PROPERTIES);
+
+        klass.add_signal(
+            "ping",
+            SignalFlags::RUN_LAST,
+            &[i32::static_type()],
+            glib::Type::Unit,
+        );
+        klass.add_signal(
+            "add",
+            SignalFlags::RUN_LAST,
+            &[i32::static_type(), i32::static_type()],
+            i32::static_type(),
+        );
+    }
+
+    fn new() -> Self {
+        BenchObject {
+            number: Cell::new(0),
+        }
+    }
+}
+
+impl ObjectImpl for BenchObject {
+    fn set_property(&self, _obj: &Object, id: usize, value: &Value) {
+        match PROPERTIES[id] {
+            subclass::Property("number", ..) => {
+                self.number.set(
+                    value
+                        .get_some()
+                        .expect("type conformity checked by 'Object::set_property'"),
+                );
+            }
+            _ => unimplemented!(),
+        }
+    }
+
+    fn get_property(&self, _obj: &Object, id: usize) -> Result<Value, ()> {
+        match PROPERTIES[id] {
+            subclass::Property("number", ..) => Ok(self.number.get().to_value()),
+            _ => unimplemented!(),
+        }
+    }
+}
+
+fn new_object() -> Object {
+    Object::new(BenchObject::get_type(), &[]).unwrap()
+}
+
+fn bench_new(c: &mut Criterion) {
+    let type_ = BenchObject::get_type();
+    c.bench_function("object new", |b| {
+        b.iter(|| Object::new(black_box(type_), &[]).unwrap())
+    });
+    c.bench_function("object new with property", |b| {
+        b.iter(|| Object::new(black_box(type_), &[("number", &42i32)]).unwrap())
+    });
+}
+
+fn bench_property(c: &mut Criterion) {
+    let obj = new_object();
+    c.bench_function("object set_property", |b| {
+        b.iter(|| obj.set_property("number", black_box(&42i32)).unwrap())
+    });
+    c.bench_function("object get_property", |b| {
+        b.iter(|| obj.get_property(black_box("number")).unwrap())
+    });
+}
+
+fn bench_signal(c: &mut Criterion) {
+    let obj = new_object();
+    c.bench_function("signal emit without handler", |b| {
+        b.iter(|| obj.emit("ping", &[black_box(&1i32)]).unwrap())
+    });
+
+    obj.connect("ping", false, |_| None).unwrap();
+    c.bench_function("signal emit", |b| {
+        b.iter(|| obj.emit("ping", &[black_box(&1i32)]).unwrap())
+    });
+
+    obj.connect("add", false, |args| {
+        let a = args[1].get_some::<i32>().unwrap();
+        let b = args[2].get_some::<i32>().unwrap();
+        Some((a + b).to_value())
+    })
+    .unwrap();
+    c.bench_function("signal emit with return value", |b| {
+        b.iter(|| {
+            obj.emit("add", &[black_box(&1i32), black_box(&2i32)])
+                .unwrap()
+        })
+    });
+    c.bench_function("signal emit_typed", |b| {
+        let imp = BenchObject::from_instance(&obj);
+        b.iter(|| {
+            imp.emit_typed::<_, i32>("add", (black_box(1i32), black_box(2i32)))
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, bench_new, bench_property, bench_signal);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate criterion;
+extern crate glib;
+
+use criterion::{black_box, Criterion};
+use glib::prelude::*;
+use glib::Value;
+
+fn bench_numeric(c: &mut Criterion) {
+    c.bench_function("value i32 to_value", |b| {
+        b.iter(|| black_box(42i32).to_value())
+    });
+
+    let value = 42i32.to_value();
+    c.bench_function("value i32 get_some", |b| {
+        b.iter(|| black_box(&value).get_some::<i32>())
+    });
+}
+
+fn bench_string(c: &mut Criterion) {
+    c.bench_function("value str to_value", |b| {
+        b.iter(|| black_box("Hello, World!").to_value())
+    });
+
+    let value = "Hello, World!".to_value();
+    c.bench_function("value String get", |b| {
+        b.iter(|| black_box(&value).get::<String>())
+    });
+    c.bench_function("value &str get", |b| {
+        b.iter(|| black_box(&value).get::<&str>())
+    });
+}
+
+fn bench_clone(c: &mut Criterion) {
+    let value = "Hello, World!".to_value();
+    c.bench_function("value clone", |b| b.iter(|| black_box(&value).clone()));
+
+    c.bench_function("value from_type", |b| {
+        b.iter(|| Value::from_type(black_box(glib::Type::String)))
+    });
+}
+
+criterion_group!(benches, bench_numeric, bench_string, bench_clone);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate criterion;
+extern crate glib;
+
+use criterion::{black_box, Criterion};
+use glib::{FromVariant, ToVariant, Variant};
+use std::collections::HashMap;
+
+fn bench_scalar(c: &mut Criterion) {
+    c.bench_function("variant u32 to_variant", |b| {
+        b.iter(|| black_box(42u32).to_variant())
+    });
+    c.bench_function("variant str to_variant", |b| {
+        b.iter(|| black_box("Hello, World!").to_variant())
+    });
+
+    let variant = "Hello, World!".to_variant();
+    c.bench_function("variant get_str", |b| {
+        b.iter(|| black_box(&variant).get_str())
+    });
+    c.bench_function("variant String get", |b| {
+        b.iter(|| black_box(&variant).get::<String>())
+    });
+}
+
+fn bench_container(c: &mut Criterion) {
+    let vec = (0..100u32).collect::<Vec<_>>();
+    c.bench_function("variant Vec<u32> to_variant", |b| {
+        b.iter(|| black_box(&vec).to_variant())
+    });
+
+    let variant = vec.to_variant();
+    c.bench_function("variant Vec<u32> from_variant", |b| {
+        b.iter(|| <Vec<u32>>::from_variant(black_box(&variant)))
+    });
+
+    let tuple = ("hello", 42u16, vec!["there", "you"]);
+    c.bench_function("variant tuple to_variant", |b| {
+        b.iter(|| black_box(&tuple).to_variant())
+    });
+
+    let mut map = HashMap::new();
+    for i in 0..100u32 {
+        map.insert(format!("key{}", i), i.to_variant());
+    }
+    let variant = map.to_variant();
+    c.bench_function("variant a{sv} lookup", |b| {
+        b.iter(|| black_box(&variant).lookup::<u32>(black_box("key50")))
+    });
+    c.bench_function("variant a{sv} child_value", |b| {
+        b.iter(|| black_box(&variant).get_child_value(black_box(50)))
+    });
+
+    let child = 1u32.to_variant();
+    c.bench_function("variant box", |b| {
+        b.iter(|| Variant::variant(black_box(&child)))
+    });
+}
+
+criterion_group!(benches, bench_scalar, bench_container);
+criterion_main!(benches);
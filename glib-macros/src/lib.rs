@@ -7,7 +7,9 @@ extern crate proc_macro;
 mod gboxed_derive;
 mod genum_derive;
 mod gflags_attribute;
+mod translate_newtype_derive;
 mod utils;
+mod variant_type;
 
 use proc_macro::TokenStream;
 use proc_macro_error::proc_macro_error;
@@ -46,6 +48,38 @@ pub fn gboxed_derive(input: TokenStream) -> TokenStream {
     gen.into()
 }
 
+/// Derive macro for forwarding the [`translate`] and [`glib::Value`] traits of a newtype to
+/// its only field.
+///
+/// This implements `StaticType`, `GlibPtrDefault`, `ToGlibPtr`, `FromGlibPtrNone`,
+/// `FromGlibPtrFull`, `FromGlibPtrBorrow`, `SetValue`, `SetValueOptional` and
+/// `FromValueOptional`, which allows the newtype to be used in place of the wrapped object,
+/// boxed or shared type in FFI calls and `Value`s.
+///
+/// # Example
+///
+/// ```
+/// use glib::prelude::*;
+/// use glib::TranslateNewtype;
+///
+/// #[derive(Clone, Debug, TranslateNewtype)]
+/// struct Context(glib::MainContext);
+///
+/// let context = Context(glib::MainContext::new());
+/// let value = context.to_value();
+/// assert!(value.get::<Context>().unwrap().is_some());
+/// ```
+///
+/// [`translate`]: translate/index.html
+/// [`glib::Value`]: value/struct.Value.html
+#[proc_macro_derive(TranslateNewtype)]
+#[proc_macro_error]
+pub fn translate_newtype_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let gen = translate_newtype_derive::impl_translate_newtype(&input);
+    gen.into()
+}
+
 /// Attribute macro for defining flags using the `bitflags` crate.
 /// This macro will also define a `GFlags::get_type` function and
 /// the [`glib::Value`] traits.
@@ -85,3 +119,22 @@ pub fn gflags(attr: TokenStream, item: TokenStream) -> TokenStream {
     let gen = gflags_attribute::impl_gflags(&input, &gtype_name);
     gen.into()
 }
+
+/// Macro for creating a `&'static VariantTy` from a type string that is validated at compile
+/// time.
+///
+/// # Example
+///
+/// ```
+/// use glib::variant_type;
+///
+/// let ty = variant_type!("a{sv}");
+/// assert_eq!(ty.to_str(), "a{sv}");
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn variant_type(input: TokenStream) -> TokenStream {
+    let type_string = parse_macro_input!(input as LitStr);
+    let gen = variant_type::impl_variant_type(&type_string);
+    gen.into()
+}
@@ -0,0 +1,118 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort_call_site;
+use quote::quote;
+use syn::{Data, Fields, Index, Member};
+
+use crate::utils::crate_ident_new;
+
+pub fn impl_translate_newtype(input: &syn::DeriveInput) -> TokenStream {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        abort_call_site!("derive(TranslateNewtype) does not support generic types");
+    }
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => abort_call_site!("derive(TranslateNewtype) only supports structs"),
+    };
+
+    if fields.iter().count() != 1 {
+        abort_call_site!("derive(TranslateNewtype) requires a struct with exactly one field");
+    }
+    let field = fields.iter().next().unwrap();
+    let inner = &field.ty;
+
+    let member = match field.ident {
+        Some(ref ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(0)),
+    };
+    let wrap = |value: TokenStream| match fields {
+        Fields::Named(_) => quote! { #name { #member: #value } },
+        _ => quote! { #name(#value) },
+    };
+
+    let crate_ident = crate_ident_new();
+    let ptr = quote! { <#inner as #crate_ident::translate::GlibPtrDefault>::GlibType };
+
+    let from_none = wrap(quote! { #crate_ident::translate::from_glib_none(ptr) });
+    let from_full = wrap(quote! { #crate_ident::translate::from_glib_full(ptr) });
+    let from_borrow = wrap(quote! {
+        #crate_ident::translate::from_glib_borrow::<_, #inner>(ptr).into_inner()
+    });
+    let from_value = wrap(quote! { inner });
+
+    quote! {
+        impl #crate_ident::StaticType for #name {
+            fn static_type() -> #crate_ident::Type {
+                <#inner as #crate_ident::StaticType>::static_type()
+            }
+        }
+
+        impl #crate_ident::translate::GlibPtrDefault for #name {
+            type GlibType = #ptr;
+        }
+
+        impl<'a> #crate_ident::translate::ToGlibPtr<'a, #ptr> for #name {
+            type Storage = <#inner as #crate_ident::translate::ToGlibPtr<'a, #ptr>>::Storage;
+
+            #[inline]
+            fn to_glib_none(&'a self) -> #crate_ident::translate::Stash<'a, #ptr, Self> {
+                let stash = #crate_ident::translate::ToGlibPtr::<#ptr>::to_glib_none(&self.#member);
+                #crate_ident::translate::Stash(stash.0, stash.1)
+            }
+
+            #[inline]
+            fn to_glib_full(&self) -> #ptr {
+                #crate_ident::translate::ToGlibPtr::<#ptr>::to_glib_full(&self.#member)
+            }
+        }
+
+        impl #crate_ident::translate::FromGlibPtrNone<#ptr> for #name {
+            #[inline]
+            unsafe fn from_glib_none(ptr: #ptr) -> Self {
+                #from_none
+            }
+        }
+
+        impl #crate_ident::translate::FromGlibPtrFull<#ptr> for #name {
+            #[inline]
+            unsafe fn from_glib_full(ptr: #ptr) -> Self {
+                #from_full
+            }
+        }
+
+        impl #crate_ident::translate::FromGlibPtrBorrow<#ptr> for #name {
+            #[inline]
+            unsafe fn from_glib_borrow(ptr: #ptr) -> #crate_ident::translate::Borrowed<Self> {
+                #crate_ident::translate::Borrowed::new(#from_borrow)
+            }
+        }
+
+        impl #crate_ident::value::SetValue for #name {
+            unsafe fn set_value(value: &mut #crate_ident::value::Value, this: &Self) {
+                #crate_ident::value::SetValue::set_value(value, &this.#member)
+            }
+        }
+
+        impl #crate_ident::value::SetValueOptional for #name {
+            unsafe fn set_value_optional(value: &mut #crate_ident::value::Value, this: Option<&Self>) {
+                #crate_ident::value::SetValueOptional::set_value_optional(
+                    value,
+                    this.map(|this| &this.#member),
+                )
+            }
+        }
+
+        impl<'a> #crate_ident::value::FromValueOptional<'a> for #name {
+            unsafe fn from_value_optional(value: &'a #crate_ident::value::Value) -> Option<Self> {
+                <#inner as #crate_ident::value::FromValueOptional<'a>>::from_value_optional(value)
+                    .map(|inner| #from_value)
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::LitStr;
+
+use crate::utils::crate_ident_new;
+
+// Scans a single complete type at the start of `s` and returns the remainder, following the
+// grammar of `g_variant_type_string_scan()`.
+fn scan(s: &[u8]) -> Result<&[u8], String> {
+    let (c, rest) = match s.split_first() {
+        Some((c, rest)) => (*c, rest),
+        None => return Err("unexpected end of type string".into()),
+    };
+
+    match c {
+        b'b' | b'y' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'h' | b'd' | b's' | b'o'
+        | b'g' | b'v' | b'r' | b'*' | b'?' => Ok(rest),
+        b'a' | b'm' => scan(rest),
+        b'(' => {
+            let mut rest = rest;
+            loop {
+                match rest.first() {
+                    Some(b')') => return Ok(&rest[1..]),
+                    Some(_) => rest = scan(rest)?,
+                    None => return Err("unterminated tuple type".into()),
+                }
+            }
+        }
+        b'{' => {
+            match rest.first() {
+                Some(b'b') | Some(b'y') | Some(b'n') | Some(b'q') | Some(b'i') | Some(b'u')
+                | Some(b'x') | Some(b't') | Some(b'h') | Some(b'd') | Some(b's') | Some(b'o')
+                | Some(b'g') | Some(b'?') => (),
+                _ => return Err("dictionary entry keys must be basic types".into()),
+            }
+            let rest = scan(&rest[1..])?;
+            match rest.split_first() {
+                Some((b'}', rest)) => Ok(rest),
+                _ => Err("dictionary entries must have exactly one key and one value".into()),
+            }
+        }
+        c => Err(format!("invalid character '{}' in type string", c as char)),
+    }
+}
+
+fn validate(s: &str) -> Result<(), String> {
+    match scan(s.as_bytes())? {
+        [] => Ok(()),
+        _ => Err("type string must contain exactly one complete type".into()),
+    }
+}
+
+pub fn impl_variant_type(input: &LitStr) -> TokenStream {
+    let type_string = input.value();
+    if let Err(err) = validate(&type_string) {
+        abort!(
+            input,
+            "Invalid variant type string \"{}\": {}",
+            type_string,
+            err
+        );
+    }
+
+    let crate_ident = crate_ident_new();
+
+    quote! {
+        unsafe { #crate_ident::VariantTy::from_str_unchecked(#input) }
+    }
+}
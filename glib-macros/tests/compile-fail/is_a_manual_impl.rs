@@ -0,0 +1,27 @@
+use glib::gobject_sys;
+
+glib::glib_wrapper! {
+    pub struct Foo(Object<gobject_sys::GObject, gobject_sys::GObjectClass, FooClass>);
+
+    match fn {
+        get_type => || gobject_sys::g_object_get_type(),
+    }
+}
+
+glib::glib_wrapper! {
+    pub struct Bar(Object<gobject_sys::GObject, gobject_sys::GObjectClass, BarClass>);
+
+    match fn {
+        get_type => || gobject_sys::g_object_get_type(),
+    }
+}
+
+impl AsRef<Bar> for Foo {
+    fn as_ref(&self) -> &Bar {
+        unimplemented!()
+    }
+}
+
+unsafe impl glib::object::IsA<Bar> for Foo {}
+
+fn main() {}
@@ -2,10 +2,10 @@
 // See the COPYRIGHT file at the top-level directory of this distribution.
 // Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
 
-use ::glib_macros::{gflags, GBoxed, GEnum};
+use ::glib_macros::{gflags, variant_type, GBoxed, GEnum, TranslateNewtype};
 use glib::prelude::*;
 use glib::subclass::prelude::*;
-use glib::translate::{FromGlib, ToGlib};
+use glib::translate::{from_glib_none, FromGlib, ToGlib, ToGlibPtr};
 
 #[test]
 fn derive_genum() {
@@ -95,6 +95,33 @@ fn derive_gboxed_nullable() {
     assert_eq!(None, v.get::<&MyNullableBoxed>().unwrap());
 }
 
+#[test]
+fn derive_translate_newtype() {
+    #[derive(Clone, Debug, PartialEq, TranslateNewtype)]
+    struct Context(glib::MainContext);
+
+    #[derive(Clone, Debug, PartialEq, TranslateNewtype)]
+    struct NamedContext {
+        inner: glib::MainContext,
+    }
+
+    assert_eq!(Context::static_type(), glib::MainContext::static_type());
+
+    let context = Context(glib::MainContext::new());
+    let ptr: *mut glib::glib_sys::GMainContext = context.to_glib_none().0;
+    assert_eq!(ptr, context.0.to_glib_none().0);
+    let named: NamedContext = unsafe { from_glib_none(ptr) };
+    assert_eq!(named.inner, context.0);
+
+    let v = context.to_value();
+    assert_eq!(v.type_(), glib::MainContext::static_type());
+    assert_eq!(v.get::<Context>(), Ok(Some(context.clone())));
+    assert_eq!(v.get::<NamedContext>(), Ok(Some(named)));
+
+    let v = None::<Context>.to_value();
+    assert_eq!(v.get::<Context>(), Ok(None));
+}
+
 #[test]
 fn attr_gflags() {
     #[gflags("MyFlags")]
@@ -158,3 +185,12 @@ fn attr_gflags() {
     assert!(e.get_value_by_nick("ab").is_none());
     assert!(e.get_value_by_nick("c").is_some());
 }
+
+#[test]
+fn variant_type_macro() {
+    let ty: &'static glib::VariantTy = variant_type!("a{sv}");
+    assert_eq!(ty, glib::VariantTy::new("a{sv}").unwrap());
+
+    let ty = variant_type!("(sm(ii)a{ya(ias)})");
+    assert_eq!(ty.to_str(), "(sm(ii)a{ya(ias)})");
+}
@@ -0,0 +1,442 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Typed wrapper around `GArray`.
+//!
+//! Elements are moved into the array's storage. If `T` has drop glue, a clear function is
+//! installed with `g_array_set_clear_func()` so that elements are dropped whenever GLib
+//! removes them or frees the array.
+//!
+//! # Examples
+//!
+//! ```
+//! use glib::Array;
+//!
+//! let mut a = Array::new();
+//! a.push(String::from("c"));
+//! a.push(String::from("a"));
+//! a.push(String::from("b"));
+//! a.sort_by(|a, b| a.cmp(b));
+//! assert_eq!(a.remove_index(0), "a");
+//! assert_eq!(a.into_vec(), vec![String::from("b"), String::from("c")]);
+//! ```
+
+use glib_sys;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::slice;
+use translate::*;
+use types::{StaticType, Type};
+
+/// A growable array of `T` backed by a `GArray`.
+///
+/// Unlike `ByteArray`, this type has unique ownership of the `GArray`, so mutating methods
+/// take `&mut self`.
+pub struct Array<T: 'static>(NonNull<glib_sys::GArray>, PhantomData<T>);
+
+unsafe impl<T: Send + 'static> Send for Array<T> {}
+unsafe impl<T: Sync + 'static> Sync for Array<T> {}
+
+unsafe extern "C" fn clear_func<T>(ptr: glib_sys::gpointer) {
+    ::guard_ffi(|| {
+        ptr::drop_in_place(ptr as *mut T);
+    })
+}
+
+impl<T: 'static> Array<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert_ne!(mem::size_of::<T>(), 0, "zero-sized types are not supported");
+        unsafe {
+            let ptr = glib_sys::g_array_sized_new(
+                glib_sys::GFALSE,
+                glib_sys::GFALSE,
+                mem::size_of::<T>() as u32,
+                capacity as u32,
+            );
+            Self::from_raw(ptr)
+        }
+    }
+
+    // Takes ownership of `ptr` and installs the clear function for `T`.
+    unsafe fn from_raw(ptr: *mut glib_sys::GArray) -> Self {
+        assert!(!ptr.is_null());
+        assert_eq!(
+            glib_sys::g_array_get_element_size(ptr) as usize,
+            mem::size_of::<T>()
+        );
+        let array = Array(NonNull::new_unchecked(ptr), PhantomData);
+        array.set_clear_func(true);
+        array
+    }
+
+    fn set_clear_func(&self, enable: bool) {
+        if !mem::needs_drop::<T>() {
+            return;
+        }
+
+        unsafe {
+            glib_sys::g_array_set_clear_func(
+                self.0.as_ptr(),
+                if enable { Some(clear_func::<T>) } else { None },
+            );
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        unsafe {
+            let value = mem::ManuallyDrop::new(value);
+            glib_sys::g_array_append_vals(
+                self.0.as_ptr(),
+                &*value as *const T as glib_sys::gconstpointer,
+                1,
+            );
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove_index(self.len() - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "index out of bounds");
+        unsafe {
+            let value = mem::ManuallyDrop::new(value);
+            glib_sys::g_array_insert_vals(
+                self.0.as_ptr(),
+                index as u32,
+                &*value as *const T as glib_sys::gconstpointer,
+                1,
+            );
+        }
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it.
+    pub fn remove_index(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        unsafe {
+            let value = ptr::read(&self[index]);
+            self.set_clear_func(false);
+            glib_sys::g_array_remove_index(self.0.as_ptr(), index as u32);
+            self.set_clear_func(true);
+            value
+        }
+    }
+
+    /// Removes and returns the element at `index`, replacing it with the last element.
+    pub fn remove_index_fast(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        unsafe {
+            let value = ptr::read(&self[index]);
+            self.set_clear_func(false);
+            glib_sys::g_array_remove_index_fast(self.0.as_ptr(), index as u32);
+            self.set_clear_func(true);
+            value
+        }
+    }
+
+    /// Removes all elements, dropping them.
+    pub fn clear(&mut self) {
+        unsafe {
+            glib_sys::g_array_set_size(self.0.as_ptr(), 0);
+        }
+    }
+
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare_func: F) {
+        unsafe extern "C" fn compare_func_trampoline<T>(
+            a: glib_sys::gconstpointer,
+            b: glib_sys::gconstpointer,
+            func: glib_sys::gpointer,
+        ) -> i32 {
+            ::guard_ffi(|| {
+                let func = func as *mut &mut (dyn FnMut(&T, &T) -> Ordering);
+
+                let a = &*(a as *const T);
+                let b = &*(b as *const T);
+
+                match (*func)(a, b) {
+                    Ordering::Less => -1,
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                }
+            })
+        }
+        unsafe {
+            let mut func = compare_func;
+            let func_obj: &mut (dyn FnMut(&T, &T) -> Ordering) = &mut func;
+            let func_ptr =
+                &func_obj as *const &mut (dyn FnMut(&T, &T) -> Ordering) as glib_sys::gpointer;
+
+            glib_sys::g_array_sort_with_data(
+                self.0.as_ptr(),
+                Some(compare_func_trampoline::<T>),
+                func_ptr,
+            );
+        }
+    }
+
+    /// Moves all elements into a `Vec` and frees the `GArray`.
+    pub fn into_vec(self) -> Vec<T> {
+        unsafe {
+            let len = self.len();
+            let mut v = Vec::with_capacity(len);
+            ptr::copy_nonoverlapping(self.as_ptr(), v.as_mut_ptr(), len);
+            v.set_len(len);
+
+            // The elements are owned by the Vec now, so don't let GLib drop them
+            self.set_clear_func(false);
+            glib_sys::g_array_set_size(self.0.as_ptr(), 0);
+
+            v
+        }
+    }
+}
+
+impl<T: 'static> Drop for Array<T> {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_array_unref(self.0.as_ptr());
+        }
+    }
+}
+
+impl<T: 'static> Deref for Array<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe {
+            let mut ptr = (*self.0.as_ptr()).data as *mut T;
+            let len = (*self.0.as_ptr()).len as usize;
+            debug_assert!(!ptr.is_null() || len == 0);
+            if ptr.is_null() {
+                ptr = NonNull::dangling().as_ptr();
+            }
+            slice::from_raw_parts(ptr, len)
+        }
+    }
+}
+
+impl<T: 'static> DerefMut for Array<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe {
+            let mut ptr = (*self.0.as_ptr()).data as *mut T;
+            let len = (*self.0.as_ptr()).len as usize;
+            debug_assert!(!ptr.is_null() || len == 0);
+            if ptr.is_null() {
+                ptr = NonNull::dangling().as_ptr();
+            }
+            slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+}
+
+impl<T: 'static> Default for Array<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for Array<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Array")
+            .field("ptr", &self.0)
+            .field("data", &&self[..])
+            .finish()
+    }
+}
+
+impl<T: 'static> Extend<T> for Array<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: 'static> FromIterator<T> for Array<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut array = Self::with_capacity(iter.size_hint().0);
+        array.extend(iter);
+        array
+    }
+}
+
+impl<T: 'static> From<Vec<T>> for Array<T> {
+    fn from(v: Vec<T>) -> Self {
+        v.into_iter().collect()
+    }
+}
+
+impl<'a, T: 'static> IntoIterator for &'a Array<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: 'static> IntoIterator for &'a mut Array<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: 'static> IntoIterator for Array<T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<T: PartialEq + 'static> PartialEq for Array<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq + 'static> Eq for Array<T> {}
+
+impl<T: 'static> StaticType for Array<T> {
+    fn static_type() -> Type {
+        unsafe { from_glib(glib_sys::g_array_get_type()) }
+    }
+}
+
+#[doc(hidden)]
+impl<T: 'static> GlibPtrDefault for Array<T> {
+    type GlibType = *mut glib_sys::GArray;
+}
+
+#[doc(hidden)]
+impl<'a, T: 'static> ToGlibPtr<'a, *mut glib_sys::GArray> for Array<T> {
+    type Storage = &'a Self;
+
+    #[inline]
+    fn to_glib_none(&'a self) -> Stash<'a, *mut glib_sys::GArray, Self> {
+        Stash(self.0.as_ptr(), self)
+    }
+
+    /// Adds a reference to the `GArray`.
+    ///
+    /// The clear function stays installed, so elements are dropped once the last reference
+    /// is released. The `Array` must not be mutated while the returned reference is in use.
+    #[inline]
+    fn to_glib_full(&self) -> *mut glib_sys::GArray {
+        unsafe { glib_sys::g_array_ref(self.0.as_ptr()) }
+    }
+}
+
+#[doc(hidden)]
+impl<'a, T: 'static> ToGlibPtrMut<'a, *mut glib_sys::GArray> for Array<T> {
+    type Storage = &'a mut Self;
+
+    #[inline]
+    fn to_glib_none_mut(&'a mut self) -> StashMut<'a, *mut glib_sys::GArray, Self> {
+        StashMut(self.0.as_ptr(), self)
+    }
+}
+
+#[doc(hidden)]
+impl<T: 'static> FromGlibPtrFull<*mut glib_sys::GArray> for Array<T> {
+    #[inline]
+    unsafe fn from_glib_full(ptr: *mut glib_sys::GArray) -> Self {
+        Self::from_raw(ptr)
+    }
+}
+
+#[doc(hidden)]
+impl<T: Clone + 'static> FromGlibPtrNone<*mut glib_sys::GArray> for Array<T> {
+    #[inline]
+    unsafe fn from_glib_none(ptr: *mut glib_sys::GArray) -> Self {
+        assert!(!ptr.is_null());
+        assert_eq!(
+            glib_sys::g_array_get_element_size(ptr) as usize,
+            mem::size_of::<T>()
+        );
+        let len = (*ptr).len as usize;
+        let data = (*ptr).data as *const T;
+        if len == 0 {
+            return Self::new();
+        }
+        slice::from_raw_parts(data, len).iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn various() {
+        let mut a: Array<u32> = (0..5).collect();
+        assert_eq!(a.len(), 5);
+        a.push(10);
+        a.insert(0, 7);
+        assert_eq!(&a[..], &[7, 0, 1, 2, 3, 4, 10]);
+        assert_eq!(a.remove_index(1), 0);
+        assert_eq!(a.remove_index_fast(0), 7);
+        assert_eq!(a.pop(), Some(4));
+        a.sort_by(|a, b| b.cmp(a));
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![10, 3, 2, 1]);
+        assert_eq!(a.into_vec(), vec![10, 3, 2, 1]);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let rc = Rc::new(());
+        let mut a = Array::new();
+        for _ in 0..4 {
+            a.push(rc.clone());
+        }
+        assert_eq!(Rc::strong_count(&rc), 5);
+
+        let removed = a.remove_index(0);
+        assert_eq!(Rc::strong_count(&rc), 5);
+        drop(removed);
+        assert_eq!(Rc::strong_count(&rc), 4);
+
+        let v = a.into_vec();
+        assert_eq!(Rc::strong_count(&rc), 4);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut a = Array::new();
+        a.push(rc.clone());
+        a.push(rc.clone());
+        a.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+        a.push(rc.clone());
+        drop(a);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn to_glib_full() {
+        let a: Array<String> = vec![String::from("foo"), String::from("bar")].into();
+        let ptr = a.to_glib_full();
+        drop(a);
+        let b: Array<String> = unsafe { from_glib_full(ptr) };
+        assert_eq!(&b[..], &[String::from("foo"), String::from("bar")]);
+    }
+}
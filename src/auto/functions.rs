@@ -1183,18 +1183,6 @@ pub fn spawn_async<P: AsRef<std::path::Path>>(
     }
 }
 
-pub fn spawn_check_exit_status(exit_status: i32) -> Result<(), Error> {
-    unsafe {
-        let mut error = ptr::null_mut();
-        let _ = glib_sys::g_spawn_check_exit_status(exit_status, &mut error);
-        if error.is_null() {
-            Ok(())
-        } else {
-            Err(from_glib_full(error))
-        }
-    }
-}
-
 #[cfg(any(unix, feature = "dox"))]
 pub fn spawn_command_line_async<P: AsRef<std::ffi::OsStr>>(command_line: P) -> Result<(), Error> {
     unsafe {
@@ -14,35 +14,79 @@ use std::ptr;
 use translate::*;
 
 /// Wrapper implementations for Boxed types. See `glib_wrapper!`.
+///
+/// This can also be used directly by other crates to define wrappers for boxed types with
+/// custom memory management or `Value` handling by combining the following rules:
+///
+/// - `@generic_impl [$attrs] $name, $ffi_name` defines `pub struct $name` with the given
+///   attributes and implements all `translate` traits for it. `$name` has to implement
+///   [`BoxedMemoryManager<$ffi_name>`](boxed/trait.BoxedMemoryManager.html).
+/// - `@memory_manager_impl $name, $ffi_name, @copy $arg $expr, @free $arg $expr` implements
+///   `BoxedMemoryManager` with the given expressions, which are evaluated in an `unsafe`
+///   context with `$arg` being a pointer to `$ffi_name`. Optionally `@init $arg $expr, @clear
+///   $arg $expr` can be given for types that can be allocated on the Rust side, which
+///   additionally implements `Uninitialized`.
+/// - `@value_impl $name, $ffi_name, @get_type $expr` implements `StaticType` with the `GType`
+///   returned by `$expr` and stores the value as boxed type in a `Value`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate glib;
+///
+/// use glib::translate::*;
+///
+/// #[repr(C)]
+/// pub struct FfiPoint {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// glib_boxed_wrapper!(@generic_impl [] Point, FfiPoint);
+/// glib_boxed_wrapper!(@memory_manager_impl Point, FfiPoint,
+///     @copy ptr Box::into_raw(Box::new(FfiPoint { x: (*ptr).x, y: (*ptr).y })),
+///     @free ptr drop(Box::from_raw(ptr)));
+///
+/// fn main() {
+///     let point: Point =
+///         unsafe { from_glib_full(Box::into_raw(Box::new(FfiPoint { x: 1, y: 2 }))) };
+///
+///     let copy = point.clone();
+///     let ptr: *const FfiPoint = copy.to_glib_none().0;
+///     assert_eq!(unsafe { ((*ptr).x, (*ptr).y) }, (1, 2));
+/// }
+/// ```
+///
+/// `@value_impl` additionally needs a `GType` registered for the boxed type, e.g. from
+/// `g_boxed_type_register_static()`.
 #[macro_export]
 macro_rules! glib_boxed_wrapper {
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @copy $copy_arg:ident $copy_expr:expr,
      @free $free_arg:ident $free_expr:expr, @init $init_arg:ident $init_expr:expr, @clear $clear_arg:ident $clear_expr:expr,
      @get_type $get_type_expr:expr) => {
-        glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
-        glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr,
+        $crate::glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr,
                             @init $init_arg $init_expr, @clear $clear_arg $clear_expr);
-        glib_boxed_wrapper!(@value_impl $name, $ffi_name, @get_type $get_type_expr);
+        $crate::glib_boxed_wrapper!(@value_impl $name, $ffi_name, @get_type $get_type_expr);
     };
 
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @copy $copy_arg:ident $copy_expr:expr,
      @free $free_arg:ident $free_expr:expr, @init $init_arg:ident $init_expr:expr, @clear $clear_arg:ident $clear_expr:expr) => {
-        glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
-        glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr,
+        $crate::glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr,
                             @init $init_arg $init_expr, @clear $clear_arg $clear_expr);
     };
 
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @copy $copy_arg:ident $copy_expr:expr,
      @free $free_arg:ident $free_expr:expr) => {
-        glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
-        glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr);
+        $crate::glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr);
     };
 
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @copy $copy_arg:ident $copy_expr:expr,
      @free $free_arg:ident $free_expr:expr, @get_type $get_type_expr:expr) => {
-        glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
-        glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr);
-        glib_boxed_wrapper!(@value_impl $name, $ffi_name, @get_type $get_type_expr);
+        $crate::glib_boxed_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_boxed_wrapper!(@memory_manager_impl $name, $ffi_name, @copy $copy_arg $copy_expr, @free $free_arg $free_expr);
+        $crate::glib_boxed_wrapper!(@value_impl $name, $ffi_name, @get_type $get_type_expr);
     };
 
     (@generic_impl [$($attr:meta)*] $name:ident, $ffi_name:ty) => {
@@ -108,16 +108,18 @@ impl ByteArray {
             b: glib_sys::gconstpointer,
             func: glib_sys::gpointer,
         ) -> i32 {
-            let func = func as *mut &mut (dyn FnMut(&u8, &u8) -> Ordering);
-
-            let a = &*(a as *const u8);
-            let b = &*(b as *const u8);
-
-            match (*func)(&a, &b) {
-                Ordering::Less => -1,
-                Ordering::Equal => 0,
-                Ordering::Greater => 1,
-            }
+            ::guard_ffi(|| {
+                let func = func as *mut &mut (dyn FnMut(&u8, &u8) -> Ordering);
+
+                let a = &*(a as *const u8);
+                let b = &*(b as *const u8);
+
+                match (*func)(&a, &b) {
+                    Ordering::Less => -1,
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                }
+            })
         }
         unsafe {
             let mut func = compare_func;
@@ -63,7 +63,9 @@ impl Bytes {
         };
 
         unsafe extern "C" fn drop_box<T: AsRef<[u8]> + Send + 'static>(b: glib_sys::gpointer) {
-            let _: Box<T> = Box::from_raw(b as *mut _);
+            ::guard_ffi(|| {
+                let _: Box<T> = Box::from_raw(b as *mut _);
+            })
         }
 
         unsafe {
@@ -13,6 +13,7 @@ use libc::{c_uint, c_void};
 use gobject_sys;
 use translate::{from_glib_none, mut_override, ToGlibPtr, ToGlibPtrMut, Uninitialized};
 use types::Type;
+use value::FromValueOptional;
 use ToValue;
 use Value;
 
@@ -53,28 +54,39 @@ impl Closure {
         ) where
             F: Fn(&[Value]) -> Option<Value>,
         {
-            let values = slice::from_raw_parts(param_values as *const _, n_param_values as usize);
-            let callback: &F = &*(marshal_data as *mut _);
-            let result = callback(values);
-            if !return_value.is_null() {
-                match result {
-                    Some(result) => *return_value = result.into_raw(),
-                    None => {
-                        let result = Value::uninitialized();
-                        *return_value = result.into_raw();
+            ::guard_ffi(|| {
+                let values =
+                    slice::from_raw_parts(param_values as *const _, n_param_values as usize);
+                let callback: &F = &*(marshal_data as *mut _);
+                let result = callback(values);
+                if !return_value.is_null() {
+                    match result {
+                        Some(result) => *return_value = result.into_raw(),
+                        None => {
+                            let result = Value::new_empty();
+                            *return_value = result.into_raw();
+                        }
                     }
                 }
-            }
+            })
         }
 
-        unsafe extern "C" fn finalize<F>(
+        Closure::with_marshal(callback, Some(marshal::<F>))
+    }
+
+    /// Creates a closure that is invoked through `marshal`, which gets a pointer to `data` passed
+    /// as marshal data.
+    ///
+    /// `data` is dropped when the closure is finalized.
+    pub(crate) unsafe fn with_marshal<T>(data: T, marshal: gobject_sys::GClosureMarshal) -> Self {
+        unsafe extern "C" fn finalize<T>(
             notify_data: *mut c_void,
             _closure: *mut gobject_sys::GClosure,
-        ) where
-            F: Fn(&[Value]) -> Option<Value>,
-        {
-            let _callback: Box<F> = Box::from_raw(notify_data as *mut _);
-            // callback is dropped here.
+        ) {
+            ::guard_ffi(|| {
+                let _data: Box<T> = Box::from_raw(notify_data as *mut _);
+                // data is dropped here.
+            })
         }
 
         // Due to bitfields we have to do our own calculations here for the size of the GClosure:
@@ -86,11 +98,11 @@ impl Closure {
             + 3 * mem::size_of::<*mut c_void>() as u32;
         let closure = gobject_sys::g_closure_new_simple(size, ptr::null_mut());
         assert_ne!(closure, ptr::null_mut());
-        let callback = Box::new(callback);
-        let ptr: *mut F = Box::into_raw(callback);
+        let data = Box::new(data);
+        let ptr: *mut T = Box::into_raw(data);
         let ptr: *mut c_void = ptr as *mut _;
-        gobject_sys::g_closure_set_meta_marshal(closure, ptr, Some(marshal::<F>));
-        gobject_sys::g_closure_add_finalize_notifier(closure, ptr, Some(finalize::<F>));
+        gobject_sys::g_closure_set_meta_marshal(closure, ptr, marshal);
+        gobject_sys::g_closure_add_finalize_notifier(closure, ptr, Some(finalize::<T>));
         from_glib_none(closure)
     }
 
@@ -106,7 +118,7 @@ impl Closure {
 
     pub fn invoke_generic(&self, values: &[Value]) -> Option<Value> {
         let result = unsafe {
-            let mut result = Value::uninitialized();
+            let mut result = Value::new_empty();
             gobject_sys::g_closure_invoke(
                 self.to_glib_none().0 as *mut _,
                 result.to_glib_none_mut().0,
@@ -129,8 +141,175 @@ impl Closure {
 unsafe impl Send for Closure {}
 unsafe impl Sync for Closure {}
 
+/// Conversion of the return value of closures created by [`closure!`] and [`closure_local!`].
+///
+/// This is implemented for `()`, which returns no value, and all types implementing `ToValue`.
+///
+/// [`closure!`]: ../macro.closure.html
+/// [`closure_local!`]: ../macro.closure_local.html
+pub trait ToClosureReturnValue {
+    fn to_closure_return_value(&self) -> Option<Value>;
+}
+
+impl ToClosureReturnValue for () {
+    fn to_closure_return_value(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl<T: ToValue> ToClosureReturnValue for T {
+    fn to_closure_return_value(&self) -> Option<Value> {
+        Some(self.to_value())
+    }
+}
+
+/// Conversion of the arguments of closures created by [`closure!`] and [`closure_local!`].
+///
+/// This is implemented for all types implementing `FromValueOptional`, which panic if the
+/// value is `None`, and for `Option`s of them.
+///
+/// [`closure!`]: ../macro.closure.html
+/// [`closure_local!`]: ../macro.closure_local.html
+pub trait FromClosureArg<'a>: Sized {
+    fn from_closure_arg(value: &'a Value) -> Self;
+}
+
+impl<'a, T: FromValueOptional<'a>> FromClosureArg<'a> for T {
+    fn from_closure_arg(value: &'a Value) -> Self {
+        Option::<T>::from_closure_arg(value).expect("Closure argument is None")
+    }
+}
+
+impl<'a, T: FromValueOptional<'a>> FromClosureArg<'a> for Option<T> {
+    fn from_closure_arg(value: &'a Value) -> Self {
+        value
+            .get::<T>()
+            .unwrap_or_else(|err| panic!("Invalid closure argument: {}", err))
+    }
+}
+
+#[doc(hidden)]
+pub fn next_closure_arg<'a, T: FromClosureArg<'a>>(values: &mut slice::Iter<'a, Value>) -> T {
+    T::from_closure_arg(
+        values
+            .next()
+            .expect("Closure called with too few arguments"),
+    )
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! closure_impl {
+    ($ctor:ident, move || $body:expr) => {
+        $crate::closure_impl!(@closure $ctor, (move || $body),)
+    };
+    ($ctor:ident, move | $($arg:ident : $typ:ty),* | $body:expr) => {
+        $crate::closure_impl!(@closure $ctor, (move |$($arg: $typ),*| $body), $($typ),*)
+    };
+    ($ctor:ident, $($(@ $strength:ident$(-$var:ident-$var2:ident)?)? $($variables:ident).+ $(as $rename:ident)?),+ => @default-panic, move || $body:expr) => {
+        $crate::closure_impl!(
+            @closure $ctor,
+            ($crate::clone!($($(@ $strength$(-$var-$var2)?)? $($variables).+ $(as $rename)?),+ => @default-panic, move || $body)),
+        )
+    };
+    ($ctor:ident, $($(@ $strength:ident$(-$var:ident-$var2:ident)?)? $($variables:ident).+ $(as $rename:ident)?),+ => $(@default-return $return_value:expr,)? move || $body:expr) => {
+        $crate::closure_impl!(
+            @closure $ctor,
+            ($crate::clone!($($(@ $strength$(-$var-$var2)?)? $($variables).+ $(as $rename)?),+ => $(@default-return $return_value,)? move || $body)),
+        )
+    };
+    ($ctor:ident, $($(@ $strength:ident$(-$var:ident-$var2:ident)?)? $($variables:ident).+ $(as $rename:ident)?),+ => @default-panic, move | $($arg:ident : $typ:ty),* | $body:expr) => {
+        $crate::closure_impl!(
+            @closure $ctor,
+            ($crate::clone!($($(@ $strength$(-$var-$var2)?)? $($variables).+ $(as $rename)?),+ => @default-panic, move |$($arg: $typ),*| $body)),
+            $($typ),*
+        )
+    };
+    ($ctor:ident, $($(@ $strength:ident$(-$var:ident-$var2:ident)?)? $($variables:ident).+ $(as $rename:ident)?),+ => $(@default-return $return_value:expr,)? move | $($arg:ident : $typ:ty),* | $body:expr) => {
+        $crate::closure_impl!(
+            @closure $ctor,
+            ($crate::clone!($($(@ $strength$(-$var-$var2)?)? $($variables).+ $(as $rename)?),+ => $(@default-return $return_value,)? move |$($arg: $typ),*| $body)),
+            $($typ),*
+        )
+    };
+    (@closure $ctor:ident, ($func:expr), $($typ:ty),*) => {{
+        let func = $func;
+        $crate::Closure::$ctor(move |values: &[$crate::Value]| {
+            #[allow(unused_mut, unused_variables)]
+            let mut values = values.iter();
+            let ret = func($($crate::closure::next_closure_arg::<$typ>(&mut values)),*);
+            $crate::closure::ToClosureReturnValue::to_closure_return_value(&ret)
+        })
+    }};
+}
+
+/// Macro for creating a [`Closure`] with typed arguments and return value that can capture
+/// variables as strong or weak references, like [`clone!`].
+///
+/// All arguments must have a type annotation. They are converted from the `Value`s the closure
+/// is invoked with, in order, and the closure panics if this fails. Arguments can be declared
+/// as `Option` to also allow `None` values. The return value is converted with
+/// [`ToClosureReturnValue`], i.e. `()` returns no value.
+///
+/// The closure must be `Send` and `Sync`, see [`closure_local!`] otherwise.
+///
+/// ```
+/// use glib::closure;
+/// use std::sync::Arc;
+///
+/// let v = Arc::new(1);
+/// let closure = closure!(@weak v => @default-return 0, move |x: i32, s: Option<&str>| {
+///     assert_eq!(s, None);
+///     *v + x
+/// });
+///
+/// let ret = closure.invoke(&[&2, &None::<&str>]).unwrap();
+/// assert_eq!(ret.get_some::<i32>(), Ok(3));
+///
+/// drop(v);
+/// let ret = closure.invoke(&[&2, &None::<&str>]).unwrap();
+/// assert_eq!(ret.get_some::<i32>(), Ok(0));
+/// ```
+///
+/// [`Closure`]: struct.Closure.html
+/// [`clone!`]: macro.clone.html
+/// [`closure_local!`]: macro.closure_local.html
+/// [`ToClosureReturnValue`]: closure/trait.ToClosureReturnValue.html
+#[macro_export]
+macro_rules! closure {
+    ($($tt:tt)*) => {
+        $crate::closure_impl!(new, $($tt)*)
+    };
+}
+
+/// Like [`closure!`] but for closures that are not `Send` and `Sync`.
+///
+/// The resulting [`Closure`] can only be invoked from the thread it was created on.
+///
+/// ```
+/// use glib::closure_local;
+/// use std::rc::Rc;
+///
+/// let v = Rc::new(String::from("foo"));
+/// let closure = closure_local!(@strong v => move |s: String| format!("{}{}", v, s));
+///
+/// let ret = closure.invoke(&[&"bar"]).unwrap();
+/// assert_eq!(ret.get::<&str>(), Ok(Some("foobar")));
+/// ```
+///
+/// [`Closure`]: struct.Closure.html
+/// [`closure!`]: macro.closure.html
+#[macro_export]
+macro_rules! closure_local {
+    ($($tt:tt)*) => {
+        $crate::closure_impl!(new_local, $($tt)*)
+    };
+}
+
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
@@ -174,4 +353,48 @@ mod tests {
         let int_res = result.map(|result| result.get_some::<i32>());
         assert_eq!(int_res, Some(Ok(24)));
     }
+
+    #[test]
+    fn test_closure_macro() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+
+        let closure = closure!(@strong call_count => move |s: &str, i: i32| {
+            call_count.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(s, "test");
+            i * 2
+        });
+        let result = closure.invoke(&[&"test", &21]);
+        assert_eq!(result.map(|result| result.get_some::<i32>()), Some(Ok(42)));
+        assert_eq!(call_count.load(Ordering::Relaxed), 1);
+
+        let closure = closure!(move |s: Option<String>| assert_eq!(s, None));
+        assert!(closure.invoke(&[&None::<String>]).is_none());
+    }
+
+    #[test]
+    fn test_closure_local_macro() {
+        let v = Rc::new(Cell::new(0));
+
+        let closure = closure_local!(@weak v => move |i: i32| v.set(i));
+        assert!(closure.invoke(&[&1]).is_none());
+        assert_eq!(v.get(), 1);
+
+        let closure = closure_local!(@weak v => @default-return false, move || {
+            v.set(2);
+            true
+        });
+        let result = closure.invoke(&[]);
+        assert_eq!(
+            result.map(|result| result.get_some::<bool>()),
+            Some(Ok(true))
+        );
+        assert_eq!(v.get(), 2);
+
+        drop(v);
+        let result = closure.invoke(&[]);
+        assert_eq!(
+            result.map(|result| result.get_some::<bool>()),
+            Some(Ok(false))
+        );
+    }
 }
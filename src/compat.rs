@@ -0,0 +1,70 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Deprecated shims for code written against older versions of this crate.
+//!
+//! This is only available with the `compat` feature. It allows porting large applications to
+//! reworked APIs incrementally: the shims keep old code compiling, and the deprecation warnings
+//! point to all places that still have to be ported. The shims will be removed again in one of
+//! the next releases.
+//!
+//! ```ignore
+//! use glib::compat::prelude::*;
+//! ```
+
+use std::borrow::Cow;
+use value::FromValueOptional;
+use BoolError;
+use Value;
+
+/// Traits of this module, to be imported with `use glib::compat::prelude::*;`.
+pub mod prelude {
+    pub use super::ValueCompatExt;
+}
+
+/// Shims for the old [`Value`](../struct.Value.html) getters.
+pub trait ValueCompatExt {
+    /// Returns the contained value, or `None` if the type does not match.
+    ///
+    /// This behaves like `Value::get()` did before it returned a `Result`.
+    #[deprecated(note = "Use Value::get() and handle the type mismatch error")]
+    fn get_option<'a, T: FromValueOptional<'a>>(&'a self) -> Option<T>;
+}
+
+impl ValueCompatExt for Value {
+    fn get_option<'a, T: FromValueOptional<'a>>(&'a self) -> Option<T> {
+        self.get().ok().and_then(|v| v)
+    }
+}
+
+/// Creates a `BoolError` with the given message, like the `BoolError` constructor did before the
+/// error carried the location where it was created.
+///
+/// The location is left empty.
+#[deprecated(note = "Use the glib_bool_error! macro")]
+pub fn bool_error<Msg: Into<Cow<'static, str>>>(message: Msg) -> BoolError {
+    BoolError::new(message, "<unknown>", "<unknown>", 0)
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::prelude::*;
+    use super::*;
+    use ToValue;
+
+    #[test]
+    fn test_get_option() {
+        let v = 123i32.to_value();
+        assert_eq!(v.get_option::<i32>(), Some(123));
+        assert_eq!(v.get_option::<String>(), None);
+    }
+
+    #[test]
+    fn test_bool_error() {
+        let err = bool_error("Failed");
+        assert_eq!(err.message, "Failed");
+        assert_eq!(err.to_string(), "Failed");
+    }
+}
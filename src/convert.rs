@@ -0,0 +1,311 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Character set conversion.
+//!
+//! # Examples
+//!
+//! ```
+//! let (latin1, _) = glib::convert::convert("ä".as_bytes(), "ISO-8859-1", "UTF-8").unwrap();
+//! assert_eq!(latin1, [0xe4]);
+//! ```
+
+use glib_sys;
+use gstring::GString;
+use libc::c_char;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+use translate::*;
+use Error;
+
+// Takes ownership of a `g_malloc()`ed buffer of `len` bytes.
+unsafe fn bytes_from_glib_full(ptr: *mut c_char, len: usize) -> Vec<u8> {
+    let v = if len == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(ptr as *const u8, len).to_vec()
+    };
+    glib_sys::g_free(ptr as glib_sys::gpointer);
+    v
+}
+
+/// Converts `str` from `from_codeset` to `to_codeset`.
+///
+/// Returns the converted bytes and the number of input bytes that were converted.
+pub fn convert(
+    str: &[u8],
+    to_codeset: &str,
+    from_codeset: &str,
+) -> Result<(Vec<u8>, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut bytes_written = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_convert(
+            str.as_ptr() as *const c_char,
+            str.len() as isize,
+            to_codeset.to_glib_none().0,
+            from_codeset.to_glib_none().0,
+            &mut bytes_read,
+            &mut bytes_written,
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((bytes_from_glib_full(res, bytes_written), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// Converts `str` from `from_codeset` to `to_codeset`, replacing characters that can't be
+/// represented in the target character set with `fallback`.
+///
+/// If `fallback` is `None`, such characters are replaced with their Unicode escape `\uxxxx`.
+pub fn convert_with_fallback(
+    str: &[u8],
+    to_codeset: &str,
+    from_codeset: &str,
+    fallback: Option<&str>,
+) -> Result<(Vec<u8>, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut bytes_written = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_convert_with_fallback(
+            str.as_ptr() as *const c_char,
+            str.len() as isize,
+            to_codeset.to_glib_none().0,
+            from_codeset.to_glib_none().0,
+            fallback.to_glib_none().0,
+            &mut bytes_read,
+            &mut bytes_written,
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((bytes_from_glib_full(res, bytes_written), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// Converts `str` from the character set of the current locale to UTF-8.
+pub fn locale_to_utf8(str: &[u8]) -> Result<(GString, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_locale_to_utf8(
+            str.as_ptr() as *const c_char,
+            str.len() as isize,
+            &mut bytes_read,
+            ptr::null_mut(),
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((from_glib_full(res), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// Converts `str` from UTF-8 to the character set of the current locale.
+pub fn locale_from_utf8(str: &str) -> Result<(Vec<u8>, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut bytes_written = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_locale_from_utf8(
+            str.as_ptr() as *const c_char,
+            str.len() as isize,
+            &mut bytes_read,
+            &mut bytes_written,
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((bytes_from_glib_full(res, bytes_written), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// Converts a filename from the GLib filename encoding to UTF-8.
+pub fn filename_to_utf8<P: AsRef<Path>>(opsysstring: P) -> Result<(GString, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_filename_to_utf8(
+            opsysstring.as_ref().to_glib_none().0,
+            -1,
+            &mut bytes_read,
+            ptr::null_mut(),
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((from_glib_full(res), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// Converts a UTF-8 string to a filename in the GLib filename encoding.
+pub fn filename_from_utf8(utf8string: &str) -> Result<(PathBuf, usize), Error> {
+    unsafe {
+        let mut bytes_read = 0;
+        let mut error = ptr::null_mut();
+        let res = glib_sys::g_filename_from_utf8(
+            utf8string.as_ptr() as *const c_char,
+            utf8string.len() as isize,
+            &mut bytes_read,
+            ptr::null_mut(),
+            &mut error,
+        );
+        if error.is_null() {
+            Ok((from_glib_full(res), bytes_read))
+        } else {
+            Err(from_glib_full(error))
+        }
+    }
+}
+
+/// An incremental character set converter wrapping `GIConv`.
+#[derive(Debug)]
+pub struct IConv(glib_sys::GIConv);
+
+unsafe impl Send for IConv {}
+
+impl IConv {
+    /// Opens a converter from `from_codeset` to `to_codeset`.
+    ///
+    /// Returns `None` if the conversion is not supported.
+    pub fn new(to_codeset: &str, from_codeset: &str) -> Option<Self> {
+        let iconv = unsafe {
+            glib_sys::g_iconv_open(to_codeset.to_glib_none().0, from_codeset.to_glib_none().0)
+        };
+        if iconv as isize == -1 {
+            None
+        } else {
+            Some(IConv(iconv))
+        }
+    }
+
+    /// Converts `str` as a whole.
+    ///
+    /// Returns the converted bytes and the number of input bytes that were converted.
+    pub fn convert(&mut self, str: &[u8]) -> Result<(Vec<u8>, usize), Error> {
+        unsafe {
+            let mut bytes_read = 0;
+            let mut bytes_written = 0;
+            let mut error = ptr::null_mut();
+            let res = glib_sys::g_convert_with_iconv(
+                str.as_ptr() as *const c_char,
+                str.len() as isize,
+                self.0,
+                &mut bytes_read,
+                &mut bytes_written,
+                &mut error,
+            );
+            if error.is_null() {
+                Ok((bytes_from_glib_full(res, bytes_written), bytes_read))
+            } else {
+                Err(from_glib_full(error))
+            }
+        }
+    }
+
+    /// Converts as much of `inbuf` into `outbuf` as possible, keeping the shift state between
+    /// calls.
+    ///
+    /// Returns the return value of `iconv()` together with the number of bytes consumed from
+    /// `inbuf` and the number of bytes written to `outbuf`. A return value of `-1` signals an
+    /// error, in which case `errno` tells if the input contained an invalid or incomplete
+    /// sequence or if `outbuf` was too small.
+    pub fn iconv(&mut self, inbuf: &[u8], outbuf: &mut [u8]) -> (isize, usize, usize) {
+        unsafe {
+            let mut inbuf_ptr = inbuf.as_ptr() as *mut c_char;
+            let mut inbytes_left = inbuf.len();
+            let mut outbuf_ptr = outbuf.as_mut_ptr() as *mut c_char;
+            let mut outbytes_left = outbuf.len();
+            let res = glib_sys::g_iconv(
+                self.0,
+                &mut inbuf_ptr,
+                &mut inbytes_left,
+                &mut outbuf_ptr,
+                &mut outbytes_left,
+            );
+            (
+                res as isize,
+                inbuf.len() - inbytes_left,
+                outbuf.len() - outbytes_left,
+            )
+        }
+    }
+
+    /// Resets the shift state of the converter.
+    pub fn reset(&mut self) {
+        unsafe {
+            glib_sys::g_iconv(
+                self.0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+        }
+    }
+}
+
+impl Drop for IConv {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_iconv_close(self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let (res, read) = convert("aä".as_bytes(), "ISO-8859-1", "UTF-8").unwrap();
+        assert_eq!(res, [b'a', 0xe4]);
+        assert_eq!(read, 3);
+
+        let (res, _) = convert(&[b'a', 0xe4], "UTF-8", "ISO-8859-1").unwrap();
+        assert_eq!(res, "aä".as_bytes());
+
+        assert!(convert("aä".as_bytes(), "ASCII", "UTF-8").is_err());
+
+        let (res, _) = convert_with_fallback("aä".as_bytes(), "ASCII", "UTF-8", Some("?")).unwrap();
+        assert_eq!(res, b"a?");
+    }
+
+    #[test]
+    fn test_iconv() {
+        let mut conv = IConv::new("ISO-8859-1", "UTF-8").unwrap();
+        let (res, _) = conv.convert("aä".as_bytes()).unwrap();
+        assert_eq!(res, [b'a', 0xe4]);
+
+        let mut out = [0u8; 8];
+        let (res, read, written) = conv.iconv("aä".as_bytes(), &mut out);
+        assert_eq!((res, read, written), (0, 3, 2));
+        assert_eq!(&out[..written], &[b'a', 0xe4]);
+
+        assert!(IConv::new("invalid-codeset", "UTF-8").is_none());
+    }
+
+    #[test]
+    fn test_filename() {
+        let (path, _) = filename_from_utf8("foo/bar").unwrap();
+        assert_eq!(path, Path::new("foo/bar"));
+        let (s, _) = filename_to_utf8(&path).unwrap();
+        assert_eq!(s, "foo/bar");
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use glib_sys;
+use std::fmt;
+use std::ptr;
+use translate::*;
+use Quark;
+
+/// A keyed data list, i.e. a `GData*`.
+///
+/// Data lists associate arbitrary data with quarks and call the destroy notify of the data once
+/// it is removed or the list is cleared. Apart from owned data lists this can also be used to
+/// access the data list fields of C structs via [`from_glib_ptr_mut`].
+///
+/// [`from_glib_ptr_mut`]: #method.from_glib_ptr_mut
+#[repr(transparent)]
+pub struct DataList(*mut glib_sys::GData);
+
+impl DataList {
+    /// Creates a new empty data list.
+    pub fn new() -> Self {
+        DataList(ptr::null_mut())
+    }
+
+    /// Borrows the data list stored at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an initialized `GData*`, e.g. a field of a C struct, that stays valid
+    /// and is not accessed otherwise for the lifetime `'a`.
+    pub unsafe fn from_glib_ptr_mut<'a>(ptr: *mut *mut glib_sys::GData) -> &'a mut Self {
+        assert!(!ptr.is_null());
+        &mut *(ptr as *mut DataList)
+    }
+
+    /// Sets the data for `key` to `value`, dropping the previous data if any.
+    ///
+    /// # Safety
+    ///
+    /// This function doesn't store type information
+    pub unsafe fn set_data<T: 'static>(&mut self, key: Quark, value: T) {
+        unsafe extern "C" fn drop_value<T>(ptr: glib_sys::gpointer) {
+            ::guard_ffi(|| {
+                debug_assert!(!ptr.is_null());
+                let value: Box<T> = Box::from_raw(ptr as *mut T);
+                drop(value)
+            })
+        }
+
+        let ptr = Box::into_raw(Box::new(value)) as glib_sys::gpointer;
+        glib_sys::g_datalist_id_set_data_full(
+            &mut self.0,
+            key.to_glib(),
+            ptr,
+            Some(drop_value::<T>),
+        );
+    }
+
+    /// Returns the data for `key`, if any.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for ensuring the returned value is of a suitable type
+    pub unsafe fn get_data<T: 'static>(&self, key: Quark) -> Option<&T> {
+        let ptr = glib_sys::g_datalist_id_get_data(mut_override(&self.0), key.to_glib());
+        if ptr.is_null() {
+            None
+        } else {
+            Some(&*(ptr as *const T))
+        }
+    }
+
+    /// Removes the data for `key` without dropping it and returns it, if any.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for ensuring the returned value is of a suitable type
+    pub unsafe fn steal_data<T: 'static>(&mut self, key: Quark) -> Option<T> {
+        let ptr = glib_sys::g_datalist_id_remove_no_notify(&mut self.0, key.to_glib());
+        if ptr.is_null() {
+            None
+        } else {
+            let value: Box<T> = Box::from_raw(ptr as *mut T);
+            Some(*value)
+        }
+    }
+
+    /// Removes the data for `key`, calling its destroy notify.
+    pub fn remove_data(&mut self, key: Quark) {
+        unsafe {
+            glib_sys::g_datalist_id_set_data_full(
+                &mut self.0,
+                key.to_glib(),
+                ptr::null_mut(),
+                None,
+            );
+        }
+    }
+
+    /// Returns the keys of all data in the list.
+    pub fn keys(&self) -> Vec<Quark> {
+        unsafe extern "C" fn collect_key(
+            key: glib_sys::GQuark,
+            _data: glib_sys::gpointer,
+            user_data: glib_sys::gpointer,
+        ) {
+            ::guard_ffi(|| {
+                let keys = &mut *(user_data as *mut Vec<Quark>);
+                keys.push(from_glib(key));
+            })
+        }
+
+        let mut keys = Vec::new();
+        unsafe {
+            glib_sys::g_datalist_foreach(
+                mut_override(&self.0),
+                Some(collect_key),
+                &mut keys as *mut Vec<Quark> as glib_sys::gpointer,
+            );
+        }
+        keys
+    }
+
+    /// Returns `true` if the list contains no data.
+    pub fn is_empty(&self) -> bool {
+        // The lowest two bits of the pointer are used for flags
+        self.0 as usize & !0x3 == 0
+    }
+
+    /// Removes all data from the list, calling their destroy notifies.
+    pub fn clear(&mut self) {
+        unsafe {
+            glib_sys::g_datalist_clear(&mut self.0);
+        }
+    }
+}
+
+impl Default for DataList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for DataList {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl fmt::Debug for DataList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DataList")
+            .field("keys", &self.keys())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_data() {
+        let foo = Quark::from_string("foo");
+        let bar = Quark::from_string("bar");
+
+        let mut list = DataList::new();
+        assert!(list.is_empty());
+
+        unsafe {
+            list.set_data(foo, String::from("foo"));
+            list.set_data(bar, 123u32);
+            assert_eq!(
+                list.get_data::<String>(foo).map(String::as_str),
+                Some("foo")
+            );
+            assert_eq!(list.get_data::<u32>(bar), Some(&123));
+
+            assert_eq!(list.steal_data::<u32>(bar), Some(123));
+            assert_eq!(list.get_data::<u32>(bar), None);
+        }
+        assert_eq!(list.keys(), vec![foo]);
+
+        list.remove_data(foo);
+        assert!(list.keys().is_empty());
+    }
+
+    #[test]
+    fn test_drop() {
+        let foo = Quark::from_string("foo");
+        let bar = Quark::from_string("bar");
+        let value = Rc::new(());
+
+        let mut list = DataList::new();
+        unsafe {
+            list.set_data(foo, value.clone());
+            list.set_data(bar, value.clone());
+        }
+        assert_eq!(Rc::strong_count(&value), 3);
+
+        unsafe {
+            list.set_data(foo, ());
+        }
+        assert_eq!(Rc::strong_count(&value), 2);
+
+        drop(list);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_from_glib_ptr_mut() {
+        let foo = Quark::from_string("foo");
+        let mut data: *mut glib_sys::GData = ptr::null_mut();
+
+        unsafe {
+            glib_sys::g_datalist_init(&mut data);
+            let list = DataList::from_glib_ptr_mut(&mut data);
+            list.set_data(foo, 1i32);
+        }
+        assert!(!data.is_null());
+
+        unsafe {
+            let list = DataList::from_glib_ptr_mut(&mut data);
+            assert_eq!(list.get_data::<i32>(foo), Some(&1));
+            list.clear();
+        }
+        assert!(data.is_null());
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use DateTime;
+#[cfg(not(any(feature = "v2_62", feature = "dox")))]
+use GString;
+
+#[cfg(not(any(feature = "v2_62", feature = "dox")))]
+const USEC_PER_SECOND: i64 = 1_000_000;
+
+impl DateTime {
+    /// Formats the date time as ISO 8601 string, e.g. `2020-06-01T12:30:00.500000+02`.
+    ///
+    /// Microseconds are only included if they are not zero, and the time zone is given as
+    /// `Z` for UTC and as offset with only as much precision as needed otherwise.
+    ///
+    /// This is implemented manually with GLib versions before 2.62.
+    #[cfg(not(any(feature = "v2_62", feature = "dox")))]
+    pub fn format_iso8601(&self) -> Option<GString> {
+        let mut res = self.format("%Y-%m-%dT%H:%M:%S")?.to_string();
+
+        let usec = self.get_microsecond();
+        if usec != 0 {
+            res.push_str(&format!(".{:06}", usec));
+        }
+
+        let offset = self.get_utc_offset() / USEC_PER_SECOND;
+        if offset == 0 {
+            res.push('Z');
+        } else {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs();
+            // Same as `%:::z`, i.e. only as precise as needed
+            res.push_str(&format!("{}{:02}", sign, offset / 3600));
+            if offset % 3600 != 0 {
+                res.push_str(&format!(":{:02}", offset / 60 % 60));
+            }
+            if offset % 60 != 0 {
+                res.push_str(&format!(":{:02}", offset % 60));
+            }
+        }
+
+        Some(res.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TimeZone;
+
+    #[test]
+    fn test_format_iso8601() {
+        let dt = DateTime::new_utc(2020, 6, 1, 12, 30, 0.0).unwrap();
+        assert_eq!(dt.format_iso8601().unwrap(), "2020-06-01T12:30:00Z");
+
+        let dt = DateTime::new_utc(2020, 6, 1, 12, 30, 0.5).unwrap();
+        assert_eq!(dt.format_iso8601().unwrap(), "2020-06-01T12:30:00.500000Z");
+
+        let tz = TimeZone::new(Some("+05:30"));
+        let dt = DateTime::new(&tz, 2020, 6, 1, 12, 30, 15.0).unwrap();
+        assert_eq!(dt.format_iso8601().unwrap(), "2020-06-01T12:30:15+05:30");
+
+        let tz = TimeZone::new(Some("-02:00"));
+        let dt = DateTime::new(&tz, 1999, 12, 31, 23, 59, 59.0).unwrap();
+        assert_eq!(dt.format_iso8601().unwrap(), "1999-12-31T23:59:59-02");
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Diagnostics for finding leaked objects, e.g. because of reference cycles.
+//!
+//! This is only available with the `debug-object-tracking` feature. All objects that are
+//! referenced from Rust, or that are instances of a subclass implemented in Rust, are counted
+//! until they are finalized.
+
+use glib_sys;
+use gobject_sys;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use translate::*;
+use Type;
+
+static LIVE_OBJECTS: Lazy<Mutex<HashMap<glib_sys::GType, usize>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static TRACKED_QUARK: Lazy<glib_sys::GQuark> = Lazy::new(|| unsafe {
+    glib_sys::g_quark_from_static_string(b"glib-rs-tracked\0".as_ptr() as *const _)
+});
+
+/// Starts counting `ptr` as live object of its type until it is finalized.
+///
+/// Objects that are already tracked are ignored.
+pub(crate) unsafe fn track_object(ptr: *mut gobject_sys::GObject) {
+    unsafe extern "C" fn untrack(data: glib_sys::gpointer) {
+        ::guard_ffi(|| {
+            let type_ = data as glib_sys::GType;
+            let mut live_objects = LIVE_OBJECTS.lock().unwrap();
+            if let Some(count) = live_objects.get_mut(&type_) {
+                *count -= 1;
+                if *count == 0 {
+                    live_objects.remove(&type_);
+                }
+            }
+        })
+    }
+
+    let mut live_objects = LIVE_OBJECTS.lock().unwrap();
+    if !gobject_sys::g_object_get_qdata(ptr, *TRACKED_QUARK).is_null() {
+        return;
+    }
+
+    let type_ = (*(*ptr).g_type_instance.g_class).g_type;
+    gobject_sys::g_object_set_qdata_full(
+        ptr,
+        *TRACKED_QUARK,
+        type_ as glib_sys::gpointer,
+        Some(untrack),
+    );
+    *live_objects.entry(type_).or_insert(0) += 1;
+}
+
+/// Returns the number of live objects per type, sorted by count with the most frequent type
+/// first.
+pub fn live_objects() -> Vec<(Type, usize)> {
+    let mut res = LIVE_OBJECTS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(&type_, &count)| (from_glib(type_), count))
+        .collect::<Vec<(Type, usize)>>();
+    res.sort_by(|a, b| b.1.cmp(&a.1));
+    res
+}
+
+/// Returns the number of live objects of exactly type `type_`.
+pub fn live_object_count(type_: Type) -> usize {
+    LIVE_OBJECTS
+        .lock()
+        .unwrap()
+        .get(&type_.to_glib())
+        .copied()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subclass;
+    use subclass::prelude::*;
+    use Object;
+
+    pub struct TrackedObject;
+
+    impl ObjectSubclass for TrackedObject {
+        const NAME: &'static str = "TrackedObject";
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            TrackedObject
+        }
+    }
+
+    impl ObjectImpl for TrackedObject {}
+
+    #[test]
+    fn test_live_objects() {
+        let type_ = TrackedObject::get_type();
+        assert_eq!(live_object_count(type_), 0);
+
+        let obj = Object::new(type_, &[]).unwrap();
+        let obj2 = obj.clone();
+        assert_eq!(live_object_count(type_), 1);
+        assert!(live_objects().contains(&(type_, 1)));
+
+        let obj3 = Object::new(type_, &[]).unwrap();
+        assert_eq!(live_object_count(type_), 2);
+
+        drop(obj);
+        drop(obj2);
+        assert_eq!(live_object_count(type_), 1);
+        drop(obj3);
+        assert_eq!(live_object_count(type_), 0);
+        assert!(!live_objects().iter().any(|&(t, _)| t == type_));
+    }
+}
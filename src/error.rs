@@ -235,4 +235,21 @@ mod tests {
         let true_dynamic_res = glib_result_from_gboolean!(glib_sys::GTRUE, "{} message", "Dynamic");
         assert!(true_dynamic_res.is_ok());
     }
+
+    #[test]
+    fn test_value() {
+        use types::StaticType;
+        use value::ToValue;
+
+        let err = Error::new(::FileError::Failed, "Failed");
+        let v = err.to_value();
+        assert_eq!(v.type_(), Error::static_type());
+
+        let err2 = v.get::<Error>().unwrap().unwrap();
+        assert_eq!(err2.kind::<::FileError>(), Some(::FileError::Failed));
+        assert_eq!(err2.to_string(), "Failed");
+
+        let v = None::<Error>.to_value();
+        assert_eq!(v.get::<Error>(), Ok(None));
+    }
 }
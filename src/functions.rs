@@ -43,10 +43,12 @@ pub fn spawn_async_with_fds<P: AsRef<std::path::Path>, T: AsRawFd, U: AsRawFd, V
     unsafe extern "C" fn child_setup_func<P: AsRef<std::path::Path>>(
         user_data: glib_sys::gpointer,
     ) {
-        let callback: Box_<Option<Box_<dyn FnOnce() + 'static>>> =
-            Box_::from_raw(user_data as *mut _);
-        let callback = (*callback).expect("cannot get closure...");
-        callback()
+        ::guard_ffi(|| {
+            let callback: Box_<Option<Box_<dyn FnOnce() + 'static>>> =
+                Box_::from_raw(user_data as *mut _);
+            let callback = (*callback).expect("cannot get closure...");
+            callback()
+        })
     }
     let child_setup = if child_setup_data.is_some() {
         Some(child_setup_func::<P> as _)
@@ -153,10 +155,12 @@ pub fn spawn_async_with_pipes<
     unsafe extern "C" fn child_setup_func<P: AsRef<std::path::Path>>(
         user_data: glib_sys::gpointer,
     ) {
-        let callback: Box_<Option<Box_<dyn FnOnce() + 'static>>> =
-            Box_::from_raw(user_data as *mut _);
-        let callback = (*callback).expect("cannot get closure...");
-        callback()
+        ::guard_ffi(|| {
+            let callback: Box_<Option<Box_<dyn FnOnce() + 'static>>> =
+                Box_::from_raw(user_data as *mut _);
+            let callback = (*callback).expect("cannot get closure...");
+            callback()
+        })
     }
     let child_setup = if child_setup_data.is_some() {
         Some(child_setup_func::<P> as _)
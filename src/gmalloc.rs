@@ -0,0 +1,360 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Owned memory allocated with `g_malloc()`.
+//!
+//! C functions returning arrays or structs with `transfer full` usually expect the caller to
+//! free them with `g_free()`. Wrapping them in a `GMallocVec` or `GMallocBox` allows using them
+//! from Rust without copying them into Rust-allocated memory first.
+//!
+//! # Examples
+//!
+//! ```
+//! use glib::GMallocVec;
+//!
+//! let v = GMallocVec::from(vec![1u32, 2, 3]);
+//! let (ptr, len) = v.into_raw();
+//! // ... pass `ptr` to C with `transfer full` or get it back from C ...
+//! let v = unsafe { GMallocVec::from_raw_parts(ptr, len) };
+//! assert_eq!(&v[..], &[1, 2, 3]);
+//! ```
+
+use glib_sys;
+use std::fmt;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+// Minimum alignment guaranteed by `g_malloc()`, which is the one of the system `malloc()`.
+const MALLOC_ALIGNMENT: usize = 2 * mem::size_of::<usize>();
+
+fn assert_malloc_compatible<T>() {
+    assert_ne!(mem::size_of::<T>(), 0, "zero-sized types are not supported");
+    assert!(
+        mem::align_of::<T>() <= MALLOC_ALIGNMENT,
+        "types with an alignment larger than the one of g_malloc() are not supported"
+    );
+}
+
+/// A fixed-size array of `T` allocated with `g_malloc()`.
+///
+/// The elements are dropped and the memory is freed with `g_free()` when the `GMallocVec` is
+/// dropped.
+pub struct GMallocVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+}
+
+unsafe impl<T: Send> Send for GMallocVec<T> {}
+unsafe impl<T: Sync> Sync for GMallocVec<T> {}
+
+impl<T> GMallocVec<T> {
+    /// Creates an empty `GMallocVec` without allocating.
+    pub fn new() -> Self {
+        GMallocVec {
+            ptr: NonNull::dangling(),
+            len: 0,
+        }
+    }
+
+    /// Takes ownership of `len` elements of `T` starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated with `g_malloc()` or one of its variants and contain `len`
+    /// initialized elements, or be `NULL` if `len` is 0.
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize) -> Self {
+        assert_ne!(mem::size_of::<T>(), 0, "zero-sized types are not supported");
+        assert!(!ptr.is_null() || len == 0);
+        if len == 0 {
+            glib_sys::g_free(ptr as glib_sys::gpointer);
+            return Self::new();
+        }
+
+        GMallocVec {
+            ptr: NonNull::new_unchecked(ptr),
+            len,
+        }
+    }
+
+    /// Returns the pointer to the elements and their number, passing ownership to the caller.
+    ///
+    /// The pointer is `NULL` if there are no elements.
+    pub fn into_raw(self) -> (*mut T, usize) {
+        let res = (self.as_raw_ptr(), self.len);
+        mem::forget(self);
+        res
+    }
+
+    /// Returns the pointer to the elements, or `NULL` if there are none.
+    pub fn as_raw_ptr(&self) -> *mut T {
+        if self.len == 0 {
+            ptr::null_mut()
+        } else {
+            self.ptr.as_ptr()
+        }
+    }
+
+    /// Moves the elements into a Rust-allocated `Vec`.
+    pub fn into_vec(self) -> Vec<T> {
+        let (ptr, len) = self.into_raw();
+        let mut v = Vec::with_capacity(len);
+        unsafe {
+            if !ptr.is_null() {
+                ptr::copy_nonoverlapping(ptr, v.as_mut_ptr(), len);
+                v.set_len(len);
+                glib_sys::g_free(ptr as glib_sys::gpointer);
+            }
+        }
+        v
+    }
+}
+
+impl<T> Drop for GMallocVec<T> {
+    fn drop(&mut self) {
+        // Empty arrays never own an allocation
+        if self.len == 0 {
+            return;
+        }
+
+        unsafe {
+            ptr::drop_in_place(slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+            glib_sys::g_free(self.ptr.as_ptr() as glib_sys::gpointer);
+        }
+    }
+}
+
+impl<T> Deref for GMallocVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for GMallocVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Default for GMallocVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GMallocVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for GMallocVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq> Eq for GMallocVec<T> {}
+
+/// # Panics
+///
+/// Panics if `T` is zero-sized or needs a larger alignment than `g_malloc()` guarantees.
+impl<T> From<Vec<T>> for GMallocVec<T> {
+    fn from(mut v: Vec<T>) -> Self {
+        assert_malloc_compatible::<T>();
+        if v.is_empty() {
+            return Self::new();
+        }
+
+        unsafe {
+            let len = v.len();
+            let ptr = glib_sys::g_malloc_n(len, mem::size_of::<T>()) as *mut T;
+            ptr::copy_nonoverlapping(v.as_ptr(), ptr, len);
+            v.set_len(0);
+            Self::from_raw_parts(ptr, len)
+        }
+    }
+}
+
+impl<T> From<GMallocVec<T>> for Vec<T> {
+    fn from(v: GMallocVec<T>) -> Self {
+        v.into_vec()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GMallocVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut GMallocVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A single `T` allocated with `g_malloc()`.
+///
+/// The value is dropped and the memory is freed with `g_free()` when the `GMallocBox` is
+/// dropped.
+pub struct GMallocBox<T>(NonNull<T>);
+
+unsafe impl<T: Send> Send for GMallocBox<T> {}
+unsafe impl<T: Sync> Sync for GMallocBox<T> {}
+
+impl<T> GMallocBox<T> {
+    /// Moves `value` into memory allocated with `g_malloc()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized or needs a larger alignment than `g_malloc()` guarantees.
+    pub fn new(value: T) -> Self {
+        assert_malloc_compatible::<T>();
+        unsafe {
+            let ptr = glib_sys::g_malloc(mem::size_of::<T>()) as *mut T;
+            ptr::write(ptr, value);
+            Self::from_raw(ptr)
+        }
+    }
+
+    /// Takes ownership of the value at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated with `g_malloc()` or one of its variants and contain an
+    /// initialized `T`.
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        assert!(!ptr.is_null());
+        GMallocBox(NonNull::new_unchecked(ptr))
+    }
+
+    /// Returns the pointer to the value, passing ownership to the caller.
+    pub fn into_raw(self) -> *mut T {
+        let ptr = self.0.as_ptr();
+        mem::forget(self);
+        ptr
+    }
+
+    /// Moves the value out of the allocation and frees it.
+    pub fn into_inner(self) -> T {
+        unsafe {
+            let ptr = self.into_raw();
+            let value = ptr::read(ptr);
+            glib_sys::g_free(ptr as glib_sys::gpointer);
+            value
+        }
+    }
+}
+
+impl<T> Drop for GMallocBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.0.as_ptr());
+            glib_sys::g_free(self.0.as_ptr() as glib_sys::gpointer);
+        }
+    }
+}
+
+impl<T> Deref for GMallocBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.0.as_ptr() }
+    }
+}
+
+impl<T> DerefMut for GMallocBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.0.as_ptr() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GMallocBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for GMallocBox<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for GMallocBox<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn vec_from_raw_parts() {
+        let (ptr, len) = unsafe {
+            let ptr = glib_sys::g_malloc_n(3, mem::size_of::<u16>()) as *mut u16;
+            for i in 0..3 {
+                *ptr.add(i) = i as u16 * 2;
+            }
+            (ptr, 3)
+        };
+
+        let mut v = unsafe { GMallocVec::from_raw_parts(ptr, len) };
+        assert_eq!(&v[..], &[0, 2, 4]);
+        v[1] = 7;
+        assert_eq!(v.into_vec(), vec![0, 7, 4]);
+
+        let v = unsafe { GMallocVec::<u16>::from_raw_parts(ptr::null_mut(), 0) };
+        assert!(v.is_empty());
+        assert!(v.into_raw().0.is_null());
+    }
+
+    #[test]
+    fn vec_drop_elements() {
+        let rc = Rc::new(());
+        let v = GMallocVec::from(vec![rc.clone(), rc.clone()]);
+        assert_eq!(Rc::strong_count(&rc), 3);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn boxed() {
+        let rc = Rc::new(());
+        let mut b = GMallocBox::new((rc.clone(), 1));
+        b.1 = 2;
+        assert_eq!(b.1, 2);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        let b = unsafe { GMallocBox::from_raw(b.into_raw()) };
+        drop(b);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        assert_eq!(GMallocBox::new(String::from("foo")).into_inner(), "foo");
+    }
+
+    #[repr(align(64))]
+    struct OverAligned(u8);
+
+    #[test]
+    #[should_panic]
+    fn box_over_aligned() {
+        GMallocBox::new(OverAligned(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn vec_over_aligned() {
+        GMallocVec::from(vec![OverAligned(0)]);
+    }
+}
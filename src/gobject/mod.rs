@@ -8,3 +8,8 @@ pub mod auto;
 
 pub use self::auto::*;
 //pub use self::auto::functions::*;
+
+mod type_module;
+pub use self::type_module::{TypeModule, TypeModuleClass, TypeModuleExt, NONE_TYPE_MODULE};
+mod type_plugin;
+pub use self::type_plugin::{TypePlugin, TypePluginExt, NONE_TYPE_PLUGIN};
@@ -0,0 +1,58 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use gobject_sys;
+use object::IsA;
+use std::fmt;
+use translate::*;
+use TypePlugin;
+
+glib_wrapper! {
+    pub struct TypeModule(Object<gobject_sys::GTypeModule, gobject_sys::GTypeModuleClass, TypeModuleClass>) @implements TypePlugin;
+
+    match fn {
+        get_type => || gobject_sys::g_type_module_get_type(),
+    }
+}
+
+pub const NONE_TYPE_MODULE: Option<&TypeModule> = None;
+
+pub trait TypeModuleExt: 'static {
+    fn set_name(&self, name: &str);
+
+    fn unuse(&self);
+
+    fn use_(&self) -> bool;
+}
+
+impl<O: IsA<TypeModule>> TypeModuleExt for O {
+    fn set_name(&self, name: &str) {
+        unsafe {
+            gobject_sys::g_type_module_set_name(
+                self.as_ref().to_glib_none().0,
+                name.to_glib_none().0,
+            );
+        }
+    }
+
+    fn unuse(&self) {
+        unsafe {
+            gobject_sys::g_type_module_unuse(self.as_ref().to_glib_none().0);
+        }
+    }
+
+    fn use_(&self) -> bool {
+        unsafe {
+            from_glib(gobject_sys::g_type_module_use(
+                self.as_ref().to_glib_none().0,
+            ))
+        }
+    }
+}
+
+impl fmt::Display for TypeModule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypeModule")
+    }
+}
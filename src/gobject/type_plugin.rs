@@ -0,0 +1,44 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use gobject_sys;
+use object::IsA;
+use std::fmt;
+use translate::*;
+
+glib_wrapper! {
+    pub struct TypePlugin(Interface<gobject_sys::GTypePlugin>);
+
+    match fn {
+        get_type => || gobject_sys::g_type_plugin_get_type(),
+    }
+}
+
+pub const NONE_TYPE_PLUGIN: Option<&TypePlugin> = None;
+
+pub trait TypePluginExt: 'static {
+    fn unuse_plugin(&self);
+
+    fn use_plugin(&self);
+}
+
+impl<O: IsA<TypePlugin>> TypePluginExt for O {
+    fn unuse_plugin(&self) {
+        unsafe {
+            gobject_sys::g_type_plugin_unuse(self.as_ref().to_glib_none().0);
+        }
+    }
+
+    fn use_plugin(&self) {
+        unsafe {
+            gobject_sys::g_type_plugin_use(self.as_ref().to_glib_none().0);
+        }
+    }
+}
+
+impl fmt::Display for TypePlugin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypePlugin")
+    }
+}
@@ -415,6 +415,12 @@ impl StaticType for Vec<GString> {
     }
 }
 
+impl StaticType for [GString] {
+    fn static_type() -> Type {
+        unsafe { from_glib(glib_sys::g_strv_get_type()) }
+    }
+}
+
 impl<'a> FromValueOptional<'a> for GString {
     unsafe fn from_value_optional(value: &'a Value) -> Option<Self> {
         let val = value.to_glib_none().0;
@@ -441,6 +447,34 @@ impl SetValueOptional for GString {
 
 impl_from_glib_container_as_vec_string!(GString, *const c_char);
 impl_from_glib_container_as_vec_string!(GString, *mut c_char);
+impl_to_glib_container_from_slice_string!(GString, *mut c_char);
+impl_to_glib_container_from_slice_string!(GString, *const c_char);
+
+impl SetValue for [GString] {
+    unsafe fn set_value(value: &mut Value, this: &Self) {
+        let ptr: *mut *mut c_char = this.to_glib_full();
+        gobject_sys::g_value_take_boxed(value.to_glib_none_mut().0, ptr as *const libc::c_void)
+    }
+}
+
+impl SetValueOptional for [GString] {
+    unsafe fn set_value_optional(value: &mut Value, this: Option<&Self>) {
+        let ptr: *mut *mut c_char = this.to_glib_full();
+        gobject_sys::g_value_take_boxed(value.to_glib_none_mut().0, ptr as *const libc::c_void)
+    }
+}
+
+impl SetValue for Vec<GString> {
+    unsafe fn set_value(value: &mut Value, this: &Self) {
+        SetValue::set_value(value, &this[..])
+    }
+}
+
+impl SetValueOptional for Vec<GString> {
+    unsafe fn set_value_optional(value: &mut Value, this: Option<&Self>) {
+        SetValueOptional::set_value_optional(value, this.map(|v| &v[..]))
+    }
+}
 
 #[cfg(test)]
 #[allow(clippy::blacklisted_name)]
@@ -0,0 +1,212 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Bindings for the GLib test framework.
+//!
+//! This allows registering Rust test cases with GLib's test harness, e.g. for running them as
+//! part of the test suite of a C project with `gtester` or `meson test`, including TAP output
+//! with the `--tap` command line argument.
+//!
+//! Such test binaries should not use the Rust test harness, i.e. `harness = false` has to be
+//! set for them in `Cargo.toml`.
+//!
+//! ```no_run
+//! fn main() {
+//!     glib::gtest::init();
+//!
+//!     glib::gtest::add_func("/my-lib/addition", || {
+//!         assert_eq!(1 + 1, 2);
+//!     });
+//!
+//!     std::process::exit(glib::gtest::run());
+//! }
+//! ```
+
+use glib_sys;
+use libc::{c_char, c_int};
+use std::env;
+use std::ffi::CString;
+use std::panic;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use translate::*;
+use LogLevel;
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Initializes the GLib test framework with the command line arguments of the process.
+///
+/// This must be called before any other function of this module. Arguments handled by GLib,
+/// e.g. `--tap`, `-p` or `-k`, are removed from the arguments GLib stores.
+///
+/// # Panics
+///
+/// Panics if called more than once.
+pub fn init() {
+    assert!(
+        !INITIALIZED.swap(true, Ordering::SeqCst),
+        "GLib test framework already initialized"
+    );
+
+    let args = env::args()
+        .map(|arg| CString::new(arg).expect("Argument contains NUL bytes"))
+        .collect::<Vec<_>>();
+
+    // GLib keeps pointers to the arguments around, so they have to stay alive until the end
+    // of the process
+    let mut argv = args
+        .into_iter()
+        .map(CString::into_raw)
+        .chain(Some(ptr::null_mut()))
+        .collect::<Vec<*mut c_char>>();
+    let mut argc = (argv.len() - 1) as c_int;
+    let mut argv_ptr = argv.as_mut_ptr();
+    std::mem::forget(argv);
+
+    unsafe {
+        glib_sys::g_test_init(&mut argc, &mut argv_ptr, ptr::null::<c_char>());
+    }
+}
+
+/// Returns `true` if [`init`](fn.init.html) was called already.
+pub fn is_initialized() -> bool {
+    INITIALIZED.load(Ordering::SeqCst)
+}
+
+/// Adds a test case with the path `test_path`, e.g. `/my-lib/my-object/create`.
+///
+/// Test cases are run by [`run`](fn.run.html) in the order given by their paths. A panic in
+/// `func` is caught and marks the test case as failed.
+pub fn add_func<F: Fn() + Send + Sync + 'static>(test_path: &str, func: F) {
+    unsafe extern "C" fn test_func<F: Fn() + Send + Sync + 'static>(
+        user_data: glib_sys::gconstpointer,
+    ) {
+        let func: &F = &*(user_data as *const F);
+        if panic::catch_unwind(panic::AssertUnwindSafe(func)).is_err() {
+            glib_sys::g_test_fail();
+        }
+    }
+
+    unsafe extern "C" fn destroy_func<F: Fn() + Send + Sync + 'static>(
+        user_data: glib_sys::gpointer,
+    ) {
+        ::guard_ffi(|| {
+            let _func: Box<F> = Box::from_raw(user_data as *mut F);
+        })
+    }
+
+    let func: Box<F> = Box::new(func);
+    unsafe {
+        glib_sys::g_test_add_data_func_full(
+            test_path.to_glib_none().0,
+            Box::into_raw(func) as glib_sys::gconstpointer,
+            Some(test_func::<F>),
+            Some(destroy_func::<F>),
+        );
+    }
+}
+
+/// Runs all test cases added with [`add_func`](fn.add_func.html).
+///
+/// Returns `0` if all test cases passed or were skipped, which can be used as exit code of the
+/// process.
+pub fn run() -> i32 {
+    unsafe { glib_sys::g_test_run() }
+}
+
+/// Indicates that a message with the given `log_domain` and `log_level` and matching the glob
+/// `pattern` is expected to be logged.
+///
+/// Expected messages are not printed and don't abort the test, even if they are critical
+/// warnings. Use [`glib_test_assert_expected_messages!`] to check that all expected messages
+/// were logged.
+///
+/// [`glib_test_assert_expected_messages!`]: ../macro.glib_test_assert_expected_messages.html
+pub fn expect_message(log_domain: Option<&str>, log_level: LogLevel, pattern: &str) {
+    unsafe {
+        glib_sys::g_test_expect_message(
+            log_domain.to_glib_none().0,
+            log_level.to_glib(),
+            pattern.to_glib_none().0,
+        );
+    }
+}
+
+#[doc(hidden)]
+pub fn assert_expected_messages_internal(
+    log_domain: Option<&str>,
+    file: &str,
+    line: u32,
+    func: &str,
+) {
+    unsafe {
+        glib_sys::g_test_assert_expected_messages_internal(
+            log_domain.to_glib_none().0,
+            file.to_glib_none().0,
+            line as c_int,
+            func.to_glib_none().0,
+        );
+    }
+}
+
+/// Asserts that all messages passed to [`gtest::expect_message`] were logged.
+///
+/// Optionally the log domain to check can be given, which defaults to all log domains.
+///
+/// [`gtest::expect_message`]: gtest/fn.expect_message.html
+#[macro_export]
+macro_rules! glib_test_assert_expected_messages {
+    () => {
+        $crate::gtest::assert_expected_messages_internal(None, file!(), line!(), module_path!())
+    };
+    ($log_domain:expr) => {
+        $crate::gtest::assert_expected_messages_internal(
+            Some($log_domain),
+            file!(),
+            line!(),
+            module_path!(),
+        )
+    };
+}
+
+/// Marks the current test case as failed without aborting it.
+pub fn fail() {
+    unsafe { glib_sys::g_test_fail() }
+}
+
+/// Returns `true` if the current test case has failed.
+pub fn failed() -> bool {
+    unsafe { from_glib(glib_sys::g_test_failed()) }
+}
+
+/// Marks the current test case as skipped, with an optional message.
+///
+/// The test case still has to return afterwards.
+pub fn skip(msg: Option<&str>) {
+    unsafe { glib_sys::g_test_skip(msg.to_glib_none().0) }
+}
+
+/// Marks the current test case as incomplete, with an optional message.
+///
+/// The test case still has to return afterwards.
+pub fn incomplete(msg: Option<&str>) {
+    unsafe { glib_sys::g_test_incomplete(msg.to_glib_none().0) }
+}
+
+/// Prints a diagnostic message in the test output, e.g. as a TAP comment.
+pub fn message(msg: &str) {
+    unsafe {
+        glib_sys::g_test_message(b"%s\0".as_ptr() as *const _, msg.to_glib_none().0);
+    }
+}
+
+/// Makes assertions failing in the test harness not abort the current test case.
+pub fn set_nonfatal_assertions() {
+    unsafe { glib_sys::g_test_set_nonfatal_assertions() }
+}
+
+/// Returns `true` if running in a test subprocess.
+pub fn is_subprocess() -> bool {
+    unsafe { from_glib(glib_sys::g_test_subprocess()) }
+}
@@ -0,0 +1,220 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Translation helpers.
+//!
+//! These go through GLib's own gettext wrappers, so translations behave the same as for C code
+//! using `_()` and friends from `glib/gi18n.h`. Setting up the text domain is done via
+//! [`i18n_init`] or the lower-level [`bindtextdomain`], [`bind_textdomain_codeset`] and
+//! [`textdomain`] functions.
+//!
+//! ```no_run
+//! #[macro_use]
+//! extern crate glib;
+//!
+//! # fn main() {
+//! glib::i18n::i18n_init("my-app", "/usr/share/locale").unwrap();
+//!
+//! let name = "World";
+//! println!("{}", i18n!("Hello {}!", name));
+//! # }
+//! ```
+//!
+//! [`i18n_init`]: fn.i18n_init.html
+//! [`bindtextdomain`]: fn.bindtextdomain.html
+//! [`bind_textdomain_codeset`]: fn.bind_textdomain_codeset.html
+//! [`textdomain`]: fn.textdomain.html
+
+use error::BoolError;
+use glib_sys;
+use gstring::GString;
+use libc::{self, c_char};
+use std::fmt;
+use std::path::Path;
+use translate::*;
+
+mod ffi {
+    use libc::c_char;
+
+    #[cfg_attr(not(all(target_os = "linux", target_env = "gnu")), link(name = "intl"))]
+    extern "C" {
+        pub fn bindtextdomain(domainname: *const c_char, dirname: *const c_char) -> *mut c_char;
+        pub fn bind_textdomain_codeset(
+            domainname: *const c_char,
+            codeset: *const c_char,
+        ) -> *mut c_char;
+        pub fn textdomain(domainname: *const c_char) -> *mut c_char;
+    }
+}
+
+/// Translates `msgid` using the current text domain.
+pub fn gettext(msgid: &str) -> GString {
+    unsafe {
+        from_glib_none(glib_sys::g_dgettext(
+            ::std::ptr::null(),
+            msgid.to_glib_none().0,
+        ))
+    }
+}
+
+/// Translates `msgid` or `msgid_plural`, depending on `n`, using the current text domain.
+pub fn ngettext(msgid: &str, msgid_plural: &str, n: u32) -> GString {
+    unsafe {
+        from_glib_none(glib_sys::g_dngettext(
+            ::std::ptr::null(),
+            msgid.to_glib_none().0,
+            msgid_plural.to_glib_none().0,
+            n as libc::c_ulong,
+        ))
+    }
+}
+
+/// Translates `msgid` in `context` using the current text domain.
+pub fn pgettext(context: &str, msgid: &str) -> GString {
+    unsafe {
+        from_glib_none(glib_sys::g_dpgettext2(
+            ::std::ptr::null(),
+            context.to_glib_none().0,
+            msgid.to_glib_none().0,
+        ))
+    }
+}
+
+/// Sets the directory containing the message catalogs of `domain`.
+pub fn bindtextdomain<P: AsRef<Path>>(domain: &str, dir: P) -> Result<(), BoolError> {
+    unsafe {
+        let res = ffi::bindtextdomain(domain.to_glib_none().0, dir.as_ref().to_glib_none().0);
+        if res.is_null() {
+            Err(glib_bool_error!("Failed to bind text domain"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Sets the character set translations of `domain` are returned in.
+pub fn bind_textdomain_codeset(domain: &str, codeset: &str) -> Result<(), BoolError> {
+    unsafe {
+        let res = ffi::bind_textdomain_codeset(domain.to_glib_none().0, codeset.to_glib_none().0);
+        if res.is_null() {
+            Err(glib_bool_error!("Failed to set text domain codeset"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Sets the current text domain.
+pub fn textdomain(domain: &str) -> Result<(), BoolError> {
+    unsafe {
+        let res = ffi::textdomain(domain.to_glib_none().0);
+        if res.is_null() {
+            Err(glib_bool_error!("Failed to set text domain"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Sets up translations for `domain` from the catalogs in `dir`.
+///
+/// This initializes the locale from the environment, binds `domain` to `dir` with UTF-8 as
+/// codeset, as expected by GLib, and makes it the current text domain.
+pub fn i18n_init<P: AsRef<Path>>(domain: &str, dir: P) -> Result<(), BoolError> {
+    unsafe {
+        libc::setlocale(libc::LC_ALL, b"\0".as_ptr() as *const c_char);
+    }
+    bindtextdomain(domain, dir)?;
+    bind_textdomain_codeset(domain, "UTF-8")?;
+    textdomain(domain)
+}
+
+#[doc(hidden)]
+pub fn i18n_format(s: &str, args: &[&dyn fmt::Display]) -> String {
+    use std::fmt::Write;
+
+    let mut res = String::with_capacity(s.len());
+    let mut args = args.iter();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                res.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                // Translations are not under our control, so missing arguments leave the
+                // placeholder in place and extra arguments are ignored instead of panicking.
+                match args.next() {
+                    Some(arg) => write!(res, "{}", arg).unwrap(),
+                    None => res.push_str("{}"),
+                }
+            }
+            _ => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// Translates a message with the current text domain and formats it.
+///
+/// The translated string can contain `{}` placeholders that are replaced by the arguments in
+/// order, and `{{`/`}}` as escapes for literal braces. Arguments without a placeholder are
+/// ignored and placeholders without an argument are kept as is, as translations might not use
+/// all of the arguments, e.g. in the singular form of a plural message.
+///
+/// ```ignore
+/// let s = i18n!("Hello");
+/// let s = i18n!("Hello {}", name);
+/// let s = i18n!(ctx = "greeting", "Hello {}", name);
+/// let s = i18n!("One file", "{} files", n; n);
+/// ```
+#[macro_export]
+macro_rules! i18n {
+    (ctx = $ctx:expr, $msgid:expr) => {
+        $crate::i18n::pgettext($ctx, $msgid).to_string()
+    };
+    (ctx = $ctx:expr, $msgid:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::i18n_format(&$crate::i18n::pgettext($ctx, $msgid), &[$(&$arg),+])
+    };
+    ($msgid:expr, $msgid_plural:expr, $n:expr; $($arg:expr),* $(,)?) => {
+        $crate::i18n::i18n_format(
+            &$crate::i18n::ngettext($msgid, $msgid_plural, $n as u32),
+            &[$(&$arg),*],
+        )
+    };
+    ($msgid:expr) => {
+        $crate::i18n::gettext($msgid).to_string()
+    };
+    ($msgid:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::i18n_format(&$crate::i18n::gettext($msgid), &[$(&$arg),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untranslated() {
+        assert_eq!(gettext("Hello"), "Hello");
+        assert_eq!(ngettext("One file", "Many files", 1), "One file");
+        assert_eq!(ngettext("One file", "Many files", 2), "Many files");
+        assert_eq!(pgettext("greeting", "Hello"), "Hello");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(i18n_format("{} + {} = {}", &[&1, &2, &3]), "1 + 2 = 3");
+        assert_eq!(i18n_format("{{}} {}", &[&"a"]), "{} a");
+        assert_eq!(i18n!("Hello"), "Hello");
+        assert_eq!(i18n!("Hello {}", "World"), "Hello World");
+        assert_eq!(i18n!(ctx = "greeting", "Hello {}", "World"), "Hello World");
+        assert_eq!(i18n!("One file", "{} files", 3; 3), "3 files");
+        assert_eq!(i18n!("One file", "{} files", 1; 1), "One file");
+        assert_eq!(i18n_format("{} and {}", &[&1]), "1 and {}");
+    }
+}
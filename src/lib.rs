@@ -90,7 +90,7 @@ pub extern crate glib_sys;
 pub extern crate gobject_sys;
 
 extern crate glib_macros;
-pub use glib_macros::{gflags, GBoxed, GEnum};
+pub use glib_macros::{gflags, variant_type, GBoxed, GEnum, TranslateNewtype};
 
 extern crate futures_channel;
 extern crate futures_core;
@@ -98,6 +98,7 @@ extern crate futures_executor;
 extern crate futures_task;
 extern crate futures_util;
 
+pub use array::Array;
 pub use byte_array::ByteArray;
 pub use bytes::Bytes;
 pub use closure::Closure;
@@ -105,19 +106,19 @@ pub use error::{BoolError, Error};
 pub use file_error::FileError;
 pub use object::{
     Cast, InitiallyUnowned, InitiallyUnownedClass, IsA, IsClassFor, Object, ObjectClass, ObjectExt,
-    ObjectType, SendWeakRef, WeakRef,
+    ObjectType, ObjectTypeExt, OptionCastExt, SendWeakRef, TryCastExt, WeakRef,
 };
 pub use signal::{
-    signal_handler_block, signal_handler_disconnect, signal_handler_unblock,
-    signal_stop_emission_by_name, SignalHandlerId,
+    signal_add_emission_hook, signal_handler_block, signal_handler_disconnect,
+    signal_handler_unblock, signal_stop_emission_by_name, EmissionHookGuard, SignalHandlerId,
 };
 use std::ffi::CStr;
 pub use string::String;
 
 pub use enums::{EnumClass, EnumValue, FlagsBuilder, FlagsClass, FlagsValue, UserDirectory};
 pub use types::{StaticType, Type};
-pub use value::{SendValue, ToSendValue, ToValue, TypedValue, Value};
-pub use variant::{FromVariant, StaticVariantType, ToVariant, Variant};
+pub use value::{SendValue, SendValueVec, ToSendValue, ToValue, TypedValue, Value};
+pub use variant::{FromVariant, StaticVariantType, ToVariant, Variant, VariantTypeMismatchError};
 pub use variant_dict::VariantDict;
 pub use variant_iter::VariantIter;
 pub use variant_type::{VariantTy, VariantType};
@@ -147,17 +148,24 @@ mod auto;
 pub use gobject::*;
 mod gobject;
 
+mod array;
 mod byte_array;
+mod gmalloc;
+pub use gmalloc::{GMallocBox, GMallocVec};
 mod bytes;
 pub mod char;
 mod string;
 pub use char::*;
 mod checksum;
 pub mod closure;
+pub mod convert;
+pub use convert::IConv;
 mod enums;
 mod file_error;
 mod functions;
 pub use functions::*;
+pub mod i18n;
+pub use i18n::{gettext, ngettext, pgettext};
 mod key_file;
 pub mod prelude;
 pub mod signal;
@@ -170,20 +178,33 @@ pub use gstring::GString;
 pub mod types;
 mod utils;
 pub use utils::*;
+mod uri;
+pub use uri::*;
+mod spawn;
+pub use spawn::{spawn_check_exit_status, ExitStatus, SpawnError};
 mod main_context;
+#[cfg(feature = "debug-source-tracking")]
+pub use main_context::{SourceInfo, SourcesIter};
 mod main_context_channel;
+mod main_loop;
+pub use main_loop::MainLoopQuitHandle;
 pub mod value;
 pub mod variant;
 mod variant_dict;
 mod variant_iter;
 mod variant_type;
-pub use main_context_channel::{Receiver, Sender, SyncSender};
+pub use main_context_channel::{BatchSender, Receiver, Sender, SyncSender};
+mod data_list;
+pub use data_list::DataList;
 mod date;
+mod date_time;
 pub use date::Date;
 mod value_array;
 pub use value_array::ValueArray;
 mod param_spec;
 pub use param_spec::*;
+mod ptr_array;
+pub use ptr_array::{PtrArray, PtrArrayIter};
 mod quark;
 pub use quark::Quark;
 #[macro_use]
@@ -193,6 +214,8 @@ pub use log::log_set_handler;
 
 // #[cfg(any(feature = "v2_50", feature = "dox"))]
 // pub use log::log_variant;
+#[doc(hidden)]
+pub use log::return_if_fail_warning;
 pub use log::{
     log_default_handler, log_remove_handler, log_set_always_fatal, log_set_default_handler,
     log_set_fatal_mask, log_unset_default_handler, set_print_handler, set_printerr_handler,
@@ -208,16 +231,35 @@ mod bridged_logging;
 #[cfg(any(feature = "log", feature = "dox"))]
 pub use bridged_logging::{rust_log_handler, GlibLogger, GlibLoggerDomain, GlibLoggerFormat};
 
+#[macro_use]
+pub mod gtest;
+
+mod mutex;
+pub use mutex::{Mutex, MutexGuard, MutexLockFuture};
+mod rec_mutex;
+pub use rec_mutex::{RecMutex, RecMutexGuard, RecMutexLockFuture};
+
+mod send_object;
+pub use send_object::SendObject;
 pub mod send_unique;
 pub use send_unique::{SendUnique, SendUniqueCell};
 
 #[macro_use]
 pub mod subclass;
 
+#[cfg(feature = "debug-object-tracking")]
+pub mod debug;
+
+#[cfg(any(feature = "compat", feature = "dox"))]
+pub mod compat;
+
 mod main_context_futures;
 mod source_futures;
 pub use source_futures::*;
 
+mod tick;
+pub use tick::{add_tick_callback, add_tick_callback_for, tick_stream, TickCallbackId, TickInfo};
+
 mod thread_pool;
 pub use thread_pool::ThreadPool;
 
@@ -280,3 +322,22 @@ impl<T> Drop for ThreadGuard<T> {
 }
 
 unsafe impl<T> Send for ThreadGuard<T> {}
+
+/// Calls `func`, aborting the process if it panics and the `abort-on-panic` feature is enabled.
+///
+/// This is used around all Rust code that is called from C, as unwinding across the FFI
+/// boundary is undefined behaviour.
+#[cfg(feature = "abort-on-panic")]
+#[inline]
+pub(crate) fn guard_ffi<R, F: FnOnce() -> R>(func: F) -> R {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(func)) {
+        Ok(res) => res,
+        Err(_) => std::process::abort(),
+    }
+}
+
+#[cfg(not(feature = "abort-on-panic"))]
+#[inline(always)]
+pub(crate) fn guard_ffi<R, F: FnOnce() -> R>(func: F) -> R {
+    func()
+}
@@ -128,16 +128,20 @@ pub fn log_set_handler<P: Fn(&str, LogLevel, &str) + Send + Sync + 'static>(
         message: *const libc::c_char,
         user_data: glib_sys::gpointer,
     ) {
-        let log_domain: Borrowed<GString> = from_glib_borrow(log_domain);
-        let message: Borrowed<GString> = from_glib_borrow(message);
-        let callback: &P = &*(user_data as *mut _);
-        (*callback)(log_domain.as_str(), from_glib(log_level), message.as_str());
+        ::guard_ffi(|| {
+            let log_domain: Borrowed<GString> = from_glib_borrow(log_domain);
+            let message: Borrowed<GString> = from_glib_borrow(message);
+            let callback: &P = &*(user_data as *mut _);
+            (*callback)(log_domain.as_str(), from_glib(log_level), message.as_str());
+        })
     }
     let log_func = Some(log_func_func::<P> as _);
     unsafe extern "C" fn destroy_func<P: Fn(&str, LogLevel, &str) + Send + Sync + 'static>(
         data: glib_sys::gpointer,
     ) {
-        let _callback: Box_<P> = Box_::from_raw(data as *mut _);
+        ::guard_ffi(|| {
+            let _callback: Box_<P> = Box_::from_raw(data as *mut _);
+        })
     }
     let destroy_call4 = Some(destroy_func::<P> as _);
     let super_callback0: Box_<P> = log_func_data;
@@ -189,13 +193,17 @@ static PRINT_HANDLER: Lazy<Mutex<Option<Arc<PrintCallback>>>> = Lazy::new(|| Mut
 /// To set back the default print handler, use the [`unset_print_handler`] function.
 pub fn set_print_handler<P: Fn(&str) + Send + Sync + 'static>(func: P) {
     unsafe extern "C" fn func_func(string: *const libc::c_char) {
-        if let Some(callback) = match *PRINT_HANDLER.lock().expect("Failed to lock PRINT_HANDLER") {
-            Some(ref handler) => Some(Arc::clone(handler)),
-            None => None,
-        } {
-            let string: Borrowed<GString> = from_glib_borrow(string);
-            (*callback)(string.as_str())
-        }
+        ::guard_ffi(|| {
+            if let Some(callback) =
+                match *PRINT_HANDLER.lock().expect("Failed to lock PRINT_HANDLER") {
+                    Some(ref handler) => Some(Arc::clone(handler)),
+                    None => None,
+                }
+            {
+                let string: Borrowed<GString> = from_glib_borrow(string);
+                (*callback)(string.as_str())
+            }
+        })
     }
     *PRINT_HANDLER
         .lock()
@@ -216,16 +224,18 @@ static PRINTERR_HANDLER: Lazy<Mutex<Option<Arc<PrintCallback>>>> = Lazy::new(||
 /// To set back the default print handler, use the [`unset_printerr_handler`] function.
 pub fn set_printerr_handler<P: Fn(&str) + Send + Sync + 'static>(func: P) {
     unsafe extern "C" fn func_func(string: *const libc::c_char) {
-        if let Some(callback) = match *PRINTERR_HANDLER
-            .lock()
-            .expect("Failed to lock PRINTERR_HANDLER")
-        {
-            Some(ref handler) => Some(Arc::clone(handler)),
-            None => None,
-        } {
-            let string: Borrowed<GString> = from_glib_borrow(string);
-            (*callback)(string.as_str())
-        }
+        ::guard_ffi(|| {
+            if let Some(callback) = match *PRINTERR_HANDLER
+                .lock()
+                .expect("Failed to lock PRINTERR_HANDLER")
+            {
+                Some(ref handler) => Some(Arc::clone(handler)),
+                None => None,
+            } {
+                let string: Borrowed<GString> = from_glib_borrow(string);
+                (*callback)(string.as_str())
+            }
+        })
     }
     *PRINTERR_HANDLER
         .lock()
@@ -253,17 +263,19 @@ pub fn log_set_default_handler<P: Fn(&str, LogLevel, &str) + Send + Sync + 'stat
         message: *const libc::c_char,
         _user_data: glib_sys::gpointer,
     ) {
-        if let Some(callback) = match *DEFAULT_HANDLER
-            .lock()
-            .expect("Failed to lock DEFAULT_HANDLER")
-        {
-            Some(ref handler) => Some(Arc::clone(handler)),
-            None => None,
-        } {
-            let log_domain: Borrowed<GString> = from_glib_borrow(log_domain);
-            let message: Borrowed<GString> = from_glib_borrow(message);
-            (*callback)(log_domain.as_str(), from_glib(log_levels), message.as_str());
-        }
+        ::guard_ffi(|| {
+            if let Some(callback) = match *DEFAULT_HANDLER
+                .lock()
+                .expect("Failed to lock DEFAULT_HANDLER")
+            {
+                Some(ref handler) => Some(Arc::clone(handler)),
+                None => None,
+            } {
+                let log_domain: Borrowed<GString> = from_glib_borrow(log_domain);
+                let message: Borrowed<GString> = from_glib_borrow(message);
+                (*callback)(log_domain.as_str(), from_glib(log_levels), message.as_str());
+            }
+        })
     }
     *DEFAULT_HANDLER
         .lock()
@@ -575,6 +587,71 @@ macro_rules! g_debug {
     }};
 }
 
+#[doc(hidden)]
+pub fn return_if_fail_warning(log_domain: &str, function: &str, expression: &str) {
+    unsafe {
+        glib_sys::g_return_if_fail_warning(
+            log_domain.to_glib_none().0,
+            function.to_glib_none().0,
+            expression.to_glib_none().0,
+        );
+    }
+}
+
+/// Macro used to check preconditions like [g_return_if_fail].
+///
+/// [g_return_if_fail]: https://developer.gnome.org/glib/stable/glib-Warnings-and-Assertions.html#g-return-if-fail
+///
+/// If the expression evaluates to `false`, a critical warning is logged the same way as from C
+/// code and the current function returns. Unlike an assertion this does not panic, and running
+/// with `G_DEBUG=fatal-criticals` makes the failure abort the process like in C.
+///
+/// Example:
+///
+/// ```no_run
+/// use glib::g_return_if_fail;
+///
+/// fn set_size(size: i32) {
+///     g_return_if_fail!("test", size >= 0);
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! g_return_if_fail {
+    ($log_domain:expr, $expr:expr $(,)?) => {
+        if !$expr {
+            $crate::return_if_fail_warning($log_domain, module_path!(), stringify!($expr));
+            return;
+        }
+    };
+}
+
+/// Macro used to check preconditions like [g_return_val_if_fail].
+///
+/// [g_return_val_if_fail]: https://developer.gnome.org/glib/stable/glib-Warnings-and-Assertions.html#g-return-val-if-fail
+///
+/// This is the same as [`g_return_if_fail!`] but returns `$val` from the current function.
+///
+/// Example:
+///
+/// ```no_run
+/// use glib::g_return_val_if_fail;
+///
+/// fn get_item(items: &[u32], index: usize) -> u32 {
+///     g_return_val_if_fail!("test", index < items.len(), 0);
+///     items[index]
+/// }
+/// ```
+#[macro_export]
+macro_rules! g_return_val_if_fail {
+    ($log_domain:expr, $expr:expr, $val:expr $(,)?) => {
+        if !$expr {
+            $crate::return_if_fail_warning($log_domain, module_path!(), stringify!($expr));
+            return $val;
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! g_print_inner {
@@ -695,3 +772,52 @@ macro_rules! g_printerr {
 //         }
 //     }};
 // }
+
+#[cfg(all(test, any(feature = "v2_46", feature = "dox")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_return_if_fail() {
+        const DOMAIN: &str = "glib-rs-return-if-fail";
+
+        // We set the fatal level explicitly in case it's set outside of the test.
+        log_set_fatal_mask(DOMAIN, LogLevels::LEVEL_ERROR);
+
+        let criticals = Arc::new(Mutex::new(Vec::new()));
+        let criticals_clone = criticals.clone();
+        let handler = log_set_handler(
+            Some(DOMAIN),
+            LogLevels::LEVEL_CRITICAL,
+            false,
+            false,
+            move |_, _, message| criticals_clone.lock().unwrap().push(message.to_owned()),
+        );
+
+        fn check_positive(x: i32) -> bool {
+            g_return_val_if_fail!(DOMAIN, x > 0, false);
+            true
+        }
+        fn check_negative(x: i32, checked: &mut bool) {
+            g_return_if_fail!(DOMAIN, x < 0);
+            *checked = true;
+        }
+
+        assert!(check_positive(1));
+        assert!(criticals.lock().unwrap().is_empty());
+        assert!(!check_positive(-1));
+        assert_eq!(criticals.lock().unwrap().len(), 1);
+        assert!(criticals.lock().unwrap()[0].contains("x > 0"));
+
+        let mut checked = false;
+        check_negative(-1, &mut checked);
+        assert!(checked);
+        checked = false;
+        check_negative(1, &mut checked);
+        assert!(!checked);
+        assert_eq!(criticals.lock().unwrap().len(), 2);
+        assert!(criticals.lock().unwrap()[1].contains("x < 0"));
+
+        log_remove_handler(Some(DOMAIN), handler);
+    }
+}
@@ -3,9 +3,17 @@
 // Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
 
 use glib_sys::{self, gboolean, gpointer};
+#[cfg(feature = "debug-source-tracking")]
+use once_cell::sync::Lazy;
 use source::Priority;
 use std::mem;
+#[cfg(feature = "debug-source-tracking")]
+use std::sync::Mutex;
+#[cfg(feature = "debug-source-tracking")]
+use std::vec;
 use translate::*;
+#[cfg(feature = "debug-source-tracking")]
+use GString;
 use MainContext;
 use Source;
 use SourceId;
@@ -33,6 +41,46 @@ impl MainContext {
         }
     }
 
+    /// Returns an iterator over the sources attached to the main context, starting with the most
+    /// recently attached one.
+    ///
+    /// At most `limit` sources are returned. Only sources that were attached with
+    /// [`Source::attach`][attach] are known, which includes sources created with e.g.
+    /// [`timeout_source_new`][timeout_source_new], channels and spawned futures but not the ones
+    /// added with the `*_add()` functions like [`timeout_add`][timeout_add].
+    ///
+    /// This is only available with the `debug-source-tracking` feature and meant for debugging,
+    /// e.g. for finding leaked timeouts.
+    ///
+    /// [attach]: struct.Source.html#method.attach
+    /// [timeout_source_new]: fn.timeout_source_new.html
+    /// [timeout_add]: fn.timeout_add.html
+    #[cfg(feature = "debug-source-tracking")]
+    pub fn iter_sources(&self, limit: usize) -> SourcesIter {
+        let mut sources = ATTACHED_SOURCES.lock().unwrap();
+        sources.retain(|source| !source.is_destroyed());
+
+        let sources = sources
+            .iter()
+            .rev()
+            .filter(|source| source.get_context().as_ref() == Some(self))
+            .take(limit)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        SourcesIter(sources.into_iter())
+    }
+
+    /// Returns the names of all named sources attached to the main context.
+    ///
+    /// Like `iter_sources()` this only knows about sources attached with `Source::attach()`.
+    #[cfg(feature = "debug-source-tracking")]
+    pub fn list_source_names(&self) -> Vec<GString> {
+        self.iter_sources(usize::max_value())
+            .filter_map(|info| info.name)
+            .collect()
+    }
+
     /// Invokes `func` on the main context.
     pub fn invoke<F>(&self, func: F)
     where
@@ -87,15 +135,19 @@ impl MainContext {
         F: FnOnce() + 'static,
     {
         unsafe extern "C" fn trampoline<F: FnOnce() + 'static>(func: gpointer) -> gboolean {
-            let func: &mut Option<F> = &mut *(func as *mut Option<F>);
-            let func = func
-                .take()
-                .expect("MainContext::invoke() closure called multiple times");
-            func();
-            glib_sys::G_SOURCE_REMOVE
+            ::guard_ffi(|| {
+                let func: &mut Option<F> = &mut *(func as *mut Option<F>);
+                let func = func
+                    .take()
+                    .expect("MainContext::invoke() closure called multiple times");
+                func();
+                glib_sys::G_SOURCE_REMOVE
+            })
         }
         unsafe extern "C" fn destroy_closure<F: FnOnce() + 'static>(ptr: gpointer) {
-            Box::<Option<F>>::from_raw(ptr as *mut _);
+            ::guard_ffi(|| {
+                Box::<Option<F>>::from_raw(ptr as *mut _);
+            })
         }
         let func = Box::into_raw(Box::new(Some(func)));
         glib_sys::g_main_context_invoke_full(
@@ -123,6 +175,63 @@ impl MainContext {
         let _thread_default = ThreadDefaultContext::new(self);
         func()
     }
+
+    /// Returns the depth of the stack of main context dispatches on the current thread.
+    ///
+    /// This is 0 outside of any callback, 1 inside a callback dispatched from a main loop
+    /// and increases by one for every nested main loop iteration, e.g. from
+    /// [`MainLoop::run_scoped`][run_scoped], that dispatches further callbacks.
+    ///
+    /// [run_scoped]: struct.MainLoop.html#method.run_scoped
+    pub fn depth() -> u32 {
+        unsafe { glib_sys::g_main_depth() as u32 }
+    }
+}
+
+#[cfg(feature = "debug-source-tracking")]
+static ATTACHED_SOURCES: Lazy<Mutex<Vec<Source>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Remembers `source` as attached until it is destroyed.
+///
+/// Sources that were destroyed in the meantime are forgotten at the same time.
+#[cfg(feature = "debug-source-tracking")]
+pub(crate) fn track_source(source: &Source) {
+    let mut sources = ATTACHED_SOURCES.lock().unwrap();
+    sources.retain(|source| !source.is_destroyed());
+    sources.push(source.clone());
+}
+
+/// Information about a source attached to a `MainContext`.
+///
+/// See [`MainContext::iter_sources`](struct.MainContext.html#method.iter_sources).
+#[cfg(feature = "debug-source-tracking")]
+#[derive(Debug)]
+pub struct SourceInfo {
+    pub id: SourceId,
+    pub name: Option<GString>,
+    pub priority: i32,
+    pub ready_time: i64,
+}
+
+/// Iterator over the sources attached to a `MainContext`.
+///
+/// See [`MainContext::iter_sources`](struct.MainContext.html#method.iter_sources).
+#[cfg(feature = "debug-source-tracking")]
+#[derive(Debug)]
+pub struct SourcesIter(vec::IntoIter<Source>);
+
+#[cfg(feature = "debug-source-tracking")]
+impl Iterator for SourcesIter {
+    type Item = SourceInfo;
+
+    fn next(&mut self) -> Option<SourceInfo> {
+        self.0.next().map(|source| SourceInfo {
+            id: unsafe { from_glib(glib_sys::g_source_get_id(source.to_glib_none().0)) },
+            name: source.get_name(),
+            priority: source.get_priority(),
+            ready_time: source.get_ready_time(),
+        })
+    }
 }
 
 struct ThreadDefaultContext<'a>(&'a MainContext);
@@ -147,6 +256,35 @@ mod tests {
     use std::ptr;
     use std::thread;
 
+    #[cfg(feature = "debug-source-tracking")]
+    #[test]
+    fn test_iter_sources() {
+        let c = MainContext::new();
+        assert_eq!(c.iter_sources(10).count(), 0);
+
+        let idle = ::idle_source_new(Some("idle"), ::PRIORITY_LOW, || ::Continue(false));
+        let idle_id = idle.attach(Some(&c));
+        let timeout =
+            ::timeout_source_new_seconds(10, None, ::PRIORITY_DEFAULT, || ::Continue(false));
+        let timeout_id = timeout.attach(Some(&c));
+
+        let sources = c.iter_sources(10).collect::<Vec<_>>();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].id, timeout_id);
+        assert_eq!(sources[0].name, None);
+        assert_eq!(sources[0].priority, ::PRIORITY_DEFAULT.to_glib());
+        assert_eq!(sources[1].id, idle_id);
+        assert_eq!(sources[1].name.as_ref().map(|s| s.as_str()), Some("idle"));
+        assert_eq!(sources[1].priority, ::PRIORITY_LOW.to_glib());
+
+        assert_eq!(c.iter_sources(1).count(), 1);
+        assert_eq!(c.list_source_names(), vec!["idle"]);
+
+        idle.destroy();
+        timeout.destroy();
+        assert_eq!(c.iter_sources(10).count(), 0);
+    }
+
     #[test]
     fn test_invoke() {
         let c = MainContext::new();
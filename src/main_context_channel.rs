@@ -13,9 +13,12 @@ use translate::{mut_override, FromGlibPtrFull, ToGlib};
 use Continue;
 use MainContext;
 use Priority;
+use SendValue;
+use SendValueVec;
 use Source;
 use SourceId;
 use ThreadGuard;
+use ToSendValue;
 
 enum ChannelSourceState {
     NotAttached,
@@ -214,66 +217,70 @@ unsafe extern "C" fn dispatch<T, F: FnMut(T) -> Continue + 'static>(
     callback: glib_sys::GSourceFunc,
     _user_data: glib_sys::gpointer,
 ) -> glib_sys::gboolean {
-    let source = &mut *(source as *mut ChannelSource<T, F>);
-    assert!(callback.is_none());
-
-    // Set ready-time to -1 so that we won't get called again before a new item is added
-    // to the channel queue.
-    glib_sys::g_source_set_ready_time(&mut source.source, -1);
-
-    // Get a reference to the callback. This will panic if we're called from a different
-    // thread than where the source was attached to the main context.
-    let callback = source
-        .callback
-        .as_mut()
-        .expect("ChannelSource called before Receiver was attached")
-        .get_mut();
-
-    // Now iterate over all items that we currently have in the channel until it is
-    // empty again. If all senders are disconnected at some point we remove the GSource
-    // from the main context it was attached to as it will never ever be called again.
-    let channel = source
-        .channel
-        .as_ref()
-        .expect("ChannelSource without Channel");
-    loop {
-        match channel.try_recv() {
-            Err(mpsc::TryRecvError::Empty) => break,
-            Err(mpsc::TryRecvError::Disconnected) => return glib_sys::G_SOURCE_REMOVE,
-            Ok(item) => {
-                if callback(item) == Continue(false) {
-                    return glib_sys::G_SOURCE_REMOVE;
+    ::guard_ffi(|| {
+        let source = &mut *(source as *mut ChannelSource<T, F>);
+        assert!(callback.is_none());
+
+        // Set ready-time to -1 so that we won't get called again before a new item is added
+        // to the channel queue.
+        glib_sys::g_source_set_ready_time(&mut source.source, -1);
+
+        // Get a reference to the callback. This will panic if we're called from a different
+        // thread than where the source was attached to the main context.
+        let callback = source
+            .callback
+            .as_mut()
+            .expect("ChannelSource called before Receiver was attached")
+            .get_mut();
+
+        // Now iterate over all items that we currently have in the channel until it is
+        // empty again. If all senders are disconnected at some point we remove the GSource
+        // from the main context it was attached to as it will never ever be called again.
+        let channel = source
+            .channel
+            .as_ref()
+            .expect("ChannelSource without Channel");
+        loop {
+            match channel.try_recv() {
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => return glib_sys::G_SOURCE_REMOVE,
+                Ok(item) => {
+                    if callback(item) == Continue(false) {
+                        return glib_sys::G_SOURCE_REMOVE;
+                    }
                 }
             }
         }
-    }
 
-    glib_sys::G_SOURCE_CONTINUE
+        glib_sys::G_SOURCE_CONTINUE
+    })
 }
 
 unsafe extern "C" fn finalize<T, F: FnMut(T) -> Continue + 'static>(
     source: *mut glib_sys::GSource,
 ) {
-    let source = &mut *(source as *mut ChannelSource<T, F>);
+    ::guard_ffi(|| {
+        let source = &mut *(source as *mut ChannelSource<T, F>);
 
-    // Drop all memory we own by taking it out of the Options
-    let channel = source.channel.take().expect("Receiver without channel");
+        // Drop all memory we own by taking it out of the Options
+        let channel = source.channel.take().expect("Receiver without channel");
 
-    {
-        // Set the source inside the channel to None so that all senders know that there
-        // is no receiver left and wake up the condition variable if any
-        let mut inner = (channel.0).0.lock().unwrap();
-        inner.source = ChannelSourceState::Destroyed;
-        if let Some(ChannelBound { ref cond, .. }) = (channel.0).1 {
-            cond.notify_all();
+        {
+            // Set the source inside the channel to None so that all senders know that there
+            // is no receiver left and wake up the condition variable if any
+            let mut inner = (channel.0).0.lock().unwrap();
+            inner.source = ChannelSourceState::Destroyed;
+            if let Some(ChannelBound { ref cond, .. }) = (channel.0).1 {
+                cond.notify_all();
+            }
         }
-    }
 
-    let _ = source.source_funcs.take();
+        let _ = source.source_funcs.take();
 
-    // Take the callback out of the source. This will panic if the value is dropped
-    // from a different thread than where the callback was created
-    let _ = source.callback.take();
+        // Take the callback out of the source. This will panic if the value is dropped
+        // from a different thread than where the callback was created
+        let _ = source.callback.take();
+    })
 }
 
 /// A `Sender` that can be used to send items to the corresponding main context receiver.
@@ -322,6 +329,60 @@ impl<T> Drop for Sender<T> {
     }
 }
 
+/// A `BatchSender` that collects values into batches for the corresponding main context
+/// callback.
+///
+/// Values sent while the previous batch was not dispatched yet are added to that batch, so the
+/// main context is only woken up once per batch instead of once per value.
+///
+/// See [`MainContext::batch_channel()`] for how to create such a `BatchSender`.
+///
+/// [`MainContext::batch_channel()`]: struct.MainContext.html#method.batch_channel
+#[derive(Clone)]
+pub struct BatchSender {
+    batch: Arc<Mutex<SendValueVec>>,
+    sender: Sender<()>,
+}
+
+impl fmt::Debug for BatchSender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BatchSender").finish()
+    }
+}
+
+impl BatchSender {
+    /// Adds a value to the current batch.
+    pub fn send<T: ToSendValue + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<(), mpsc::SendError<SendValue>> {
+        let mut batch = self.batch.lock().unwrap();
+        // Only the first value of a batch has to wake up the main context
+        if batch.is_empty() && self.sender.send(()).is_err() {
+            return Err(mpsc::SendError(value.to_send_value()));
+        }
+        batch.push(value);
+        Ok(())
+    }
+
+    /// Adds all values of `values` to the current batch.
+    pub fn send_batch(
+        &self,
+        mut values: SendValueVec,
+    ) -> Result<(), mpsc::SendError<SendValueVec>> {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = self.batch.lock().unwrap();
+        if batch.is_empty() && self.sender.send(()).is_err() {
+            return Err(mpsc::SendError(values));
+        }
+        batch.append(&mut values);
+        Ok(())
+    }
+}
+
 /// A `SyncSender` that can be used to send items to the corresponding main context receiver.
 ///
 /// This `SyncSender` behaves the same as `std::sync::mpsc::SyncSender`.
@@ -522,6 +583,39 @@ impl MainContext {
 
         (sender, receiver)
     }
+
+    /// Creates a channel that passes values in batches to `func`, which is called on this main
+    /// context.
+    ///
+    /// All values sent to the `BatchSender` before `func` is dispatched are passed to a single
+    /// invocation of `func`, which is useful to avoid waking up the main context for every single
+    /// value if many values are produced by another thread.
+    ///
+    /// The `BatchSender` can be cloned and sent to different threads. When the last
+    /// `BatchSender` is dropped the channel is removed from the main context.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called from a thread that is not the owner of the main context.
+    pub fn batch_channel<F: FnMut(SendValueVec) + 'static>(
+        &self,
+        priority: Priority,
+        mut func: F,
+    ) -> BatchSender {
+        let batch = Arc::new(Mutex::new(SendValueVec::new()));
+        let (sender, receiver) = MainContext::channel(priority);
+
+        let batch_clone = batch.clone();
+        receiver.attach(Some(self), move |()| {
+            let values = mem::take(&mut *batch_clone.lock().unwrap());
+            if !values.is_empty() {
+                func(values);
+            }
+            Continue(true)
+        });
+
+        BatchSender { batch, sender }
+    }
 }
 
 #[cfg(test)]
@@ -565,6 +659,38 @@ mod tests {
         assert_eq!(*sum.borrow(), 6);
     }
 
+    #[test]
+    fn test_batch_channel() {
+        let c = MainContext::new();
+        let l = MainLoop::new(Some(&c), false);
+
+        c.acquire();
+
+        let batches = Rc::new(RefCell::new(Vec::new()));
+        let batches_clone = batches.clone();
+        let l_clone = l.clone();
+        let sender = c.batch_channel(Priority::default(), move |mut values| {
+            let values = values
+                .drain_as::<i32>()
+                .map(|v| v.unwrap().unwrap())
+                .collect::<Vec<_>>();
+            batches_clone.borrow_mut().push(values);
+            l_clone.quit();
+        });
+
+        thread::spawn(move || {
+            sender.send(&1).unwrap();
+            sender.send(&2).unwrap();
+            sender.send_batch((3..=5).collect()).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        l.run();
+
+        assert_eq!(*batches.borrow(), vec![vec![1, 2, 3, 4, 5]]);
+    }
+
     #[test]
     fn test_drop_sender() {
         let c = MainContext::new();
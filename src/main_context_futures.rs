@@ -62,27 +62,31 @@ impl TaskSource {
         callback: glib_sys::GSourceFunc,
         _user_data: glib_sys::gpointer,
     ) -> glib_sys::gboolean {
-        let source = &mut *(source as *mut TaskSource);
-        assert!(callback.is_none());
-
-        // Poll the TaskSource and ensure we're never called again if the
-        // contained Future resolved now.
-        if let Poll::Ready(()) = source.poll() {
-            glib_sys::G_SOURCE_REMOVE
-        } else {
-            glib_sys::G_SOURCE_CONTINUE
-        }
+        ::guard_ffi(|| {
+            let source = &mut *(source as *mut TaskSource);
+            assert!(callback.is_none());
+
+            // Poll the TaskSource and ensure we're never called again if the
+            // contained Future resolved now.
+            if let Poll::Ready(()) = source.poll() {
+                glib_sys::G_SOURCE_REMOVE
+            } else {
+                glib_sys::G_SOURCE_CONTINUE
+            }
+        })
     }
 
     unsafe extern "C" fn finalize(source: *mut glib_sys::GSource) {
-        let source = source as *mut TaskSource;
+        ::guard_ffi(|| {
+            let source = source as *mut TaskSource;
 
-        // This will panic if the future was a local future and is dropped from
-        // a different thread than where it was created.
-        ptr::drop_in_place(&mut (*source).future);
+            // This will panic if the future was a local future and is dropped from
+            // a different thread than where it was created.
+            ptr::drop_in_place(&mut (*source).future);
 
-        // Drop the waker to unref the underlying GSource
-        ptr::drop_in_place(&mut (*source).waker);
+            // Drop the waker to unref the underlying GSource
+            ptr::drop_in_place(&mut (*source).waker);
+        })
     }
 }
 
@@ -120,10 +124,12 @@ impl WakerSource {
         _callback: glib_sys::GSourceFunc,
         _user_data: glib_sys::gpointer,
     ) -> glib_sys::gboolean {
-        // Set ready-time to -1 so that we're not called again before
-        // being woken up another time.
-        glib_sys::g_source_set_ready_time(mut_override(source), -1);
-        glib_sys::G_SOURCE_CONTINUE
+        ::guard_ffi(|| {
+            // Set ready-time to -1 so that we're not called again before
+            // being woken up another time.
+            glib_sys::g_source_set_ready_time(mut_override(source), -1);
+            glib_sys::G_SOURCE_CONTINUE
+        })
     }
 }
 
@@ -0,0 +1,139 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use MainContext;
+use MainLoop;
+
+impl MainLoop {
+    /// Runs a nested main loop on `context` until it is quit via the handle passed to `func`.
+    ///
+    /// `func` is called before the loop starts running and would usually set up a callback,
+    /// e.g. for the response of a modal dialog, that calls [`MainLoopQuitHandle::quit`] with a
+    /// result. Once that happens the loop returns and the result is returned from this function.
+    /// If the handle is dropped without `quit()` being called, the loop is quit and `None` is
+    /// returned.
+    ///
+    /// # Re-entrancy
+    ///
+    /// While the nested loop runs, all sources attached to `context` are dispatched, including
+    /// the one that is currently executing the caller if this is called from a callback. Any
+    /// state the caller holds, e.g. `RefCell` borrows, stays held for the whole duration and
+    /// other callbacks can run code that assumes it is not. [`MainContext::depth`] can be used
+    /// to detect such recursion.
+    ///
+    /// [`MainLoopQuitHandle::quit`]: struct.MainLoopQuitHandle.html#method.quit
+    /// [`MainContext::depth`]: struct.MainContext.html#method.depth
+    pub fn run_scoped<T, F>(context: Option<&MainContext>, func: F) -> Option<T>
+    where
+        T: 'static,
+        F: FnOnce(MainLoopQuitHandle<T>),
+    {
+        let main_loop = MainLoop::new(context, false);
+        let result = Rc::new(RefCell::new(None));
+
+        func(MainLoopQuitHandle {
+            main_loop: main_loop.clone(),
+            result: result.clone(),
+        });
+
+        // The handle might have been used or dropped already
+        if result.borrow().is_none() {
+            main_loop.run();
+        }
+
+        let mut result = result.borrow_mut();
+        result.take().and_then(|r| r)
+    }
+}
+
+/// Handle for quitting a main loop started via [`MainLoop::run_scoped`].
+///
+/// [`MainLoop::run_scoped`]: struct.MainLoop.html#method.run_scoped
+pub struct MainLoopQuitHandle<T> {
+    main_loop: MainLoop,
+    // `None` while running, `Some(None)` if quit without a value
+    result: Rc<RefCell<Option<Option<T>>>>,
+}
+
+impl<T> MainLoopQuitHandle<T> {
+    /// Quits the main loop, making `run_scoped()` return `Some(value)`.
+    ///
+    /// Only the first call has an effect.
+    pub fn quit(&self, value: T) {
+        self.quit_internal(Some(value));
+    }
+
+    /// Returns `true` if the main loop was not quit yet.
+    pub fn is_running(&self) -> bool {
+        self.result.borrow().is_none()
+    }
+
+    fn quit_internal(&self, value: Option<T>) {
+        let mut result = self.result.borrow_mut();
+        if result.is_none() {
+            *result = Some(value);
+            self.main_loop.quit();
+        }
+    }
+}
+
+impl<T> Drop for MainLoopQuitHandle<T> {
+    fn drop(&mut self) {
+        self.quit_internal(None);
+    }
+}
+
+impl<T> fmt::Debug for MainLoopQuitHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MainLoopQuitHandle")
+            .field("main_loop", &self.main_loop)
+            .field("is_running", &self.is_running())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_scoped() {
+        let c = MainContext::new();
+
+        let res = c.with_thread_default(|| {
+            assert_eq!(MainContext::depth(), 0);
+            MainLoop::run_scoped(Some(&c), |handle| {
+                ::timeout_add_local(Duration::from_millis(10), move || {
+                    assert_eq!(MainContext::depth(), 1);
+
+                    let ctx = MainContext::ref_thread_default();
+                    let inner = MainLoop::run_scoped(Some(&ctx), |handle| {
+                        ::idle_add_local(move || {
+                            assert_eq!(MainContext::depth(), 2);
+                            handle.quit("inner");
+                            ::Continue(false)
+                        });
+                    });
+                    assert_eq!(inner, Some("inner"));
+
+                    handle.quit("outer");
+                    ::Continue(false)
+                });
+            })
+        });
+        assert_eq!(res, Some("outer"));
+    }
+
+    #[test]
+    fn test_run_scoped_dropped() {
+        let c = MainContext::new();
+
+        let res: Option<()> = MainLoop::run_scoped(Some(&c), drop);
+        assert_eq!(res, None);
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use glib_sys;
+use std::cell::UnsafeCell;
+use std::cmp;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::time::Duration;
+use translate::*;
+
+const LOCK_RETRY_MIN_DELAY: Duration = Duration::from_millis(1);
+const LOCK_RETRY_MAX_DELAY: Duration = Duration::from_millis(64);
+
+/// A mutual exclusion lock, i.e. a `GMutex`.
+///
+/// Different to `std::sync::Mutex` this does not protect any data. It is meant for sharing a
+/// lock with C code, e.g. a `GMutex` that is part of a C struct via [`from_glib_ptr`].
+///
+/// [`from_glib_ptr`]: #method.from_glib_ptr
+#[repr(transparent)]
+pub struct Mutex(UnsafeCell<glib_sys::GMutex>);
+
+unsafe impl Send for Mutex {}
+unsafe impl Sync for Mutex {}
+
+impl Mutex {
+    /// Creates a new unlocked mutex.
+    pub fn new() -> Self {
+        unsafe {
+            let mut mutex = mem::MaybeUninit::zeroed();
+            glib_sys::g_mutex_init(mutex.as_mut_ptr());
+            Mutex(UnsafeCell::new(mutex.assume_init()))
+        }
+    }
+
+    /// Borrows the mutex stored at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an initialized `GMutex` that stays valid for the lifetime `'a`.
+    pub unsafe fn from_glib_ptr<'a>(ptr: *mut glib_sys::GMutex) -> &'a Self {
+        assert!(!ptr.is_null());
+        &*(ptr as *const Mutex)
+    }
+
+    /// Locks the mutex, blocking the current thread until it is available.
+    pub fn lock(&self) -> MutexGuard {
+        unsafe {
+            glib_sys::g_mutex_lock(self.0.get());
+        }
+        MutexGuard(self, PhantomData)
+    }
+
+    /// Locks the mutex if it is currently available.
+    pub fn try_lock(&self) -> Option<MutexGuard> {
+        unsafe {
+            if from_glib(glib_sys::g_mutex_trylock(self.0.get())) {
+                Some(MutexGuard(self, PhantomData))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns a future that locks the mutex without blocking the thread.
+    ///
+    /// As a `GMutex` can't notify waiters, locking is retried with exponential backoff on the
+    /// thread default main context of the thread the future is polled on.
+    pub fn lock_future(&self) -> MutexLockFuture {
+        MutexLockFuture(LockRetry::new(self))
+    }
+}
+
+impl Default for Mutex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Mutex {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_mutex_clear(self.0.get());
+        }
+    }
+}
+
+impl fmt::Debug for Mutex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Mutex")
+    }
+}
+
+/// Guard for a locked [`Mutex`](struct.Mutex.html), unlocking it when dropped.
+///
+/// A `GMutex` must be unlocked from the thread that locked it, so this is not `Send`.
+#[must_use = "if unused the mutex will immediately be unlocked"]
+pub struct MutexGuard<'a>(&'a Mutex, PhantomData<*const ()>);
+
+impl<'a> Drop for MutexGuard<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_mutex_unlock((self.0).0.get());
+        }
+    }
+}
+
+impl<'a> fmt::Debug for MutexGuard<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MutexGuard").field(&self.0).finish()
+    }
+}
+
+/// Future returned by [`Mutex::lock_future`](struct.Mutex.html#method.lock_future).
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct MutexLockFuture<'a>(LockRetry<'a, Mutex>);
+
+impl<'a> Future for MutexLockFuture<'a> {
+    type Output = MutexGuard<'a>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<MutexGuard<'a>> {
+        self.get_mut().0.poll_lock(ctx, Mutex::try_lock)
+    }
+}
+
+/// Retries locking a mutex with exponential backoff, shared by the `lock_future()` futures of
+/// [`Mutex`] and [`RecMutex`].
+///
+/// [`Mutex`]: struct.Mutex.html
+/// [`RecMutex`]: struct.RecMutex.html
+pub(crate) struct LockRetry<'a, M> {
+    mutex: &'a M,
+    delay: Duration,
+    timeout: Option<Pin<Box<dyn Future<Output = ()> + Send + 'static>>>,
+}
+
+impl<'a, M> LockRetry<'a, M> {
+    pub(crate) fn new(mutex: &'a M) -> Self {
+        LockRetry {
+            mutex,
+            delay: LOCK_RETRY_MIN_DELAY,
+            timeout: None,
+        }
+    }
+
+    /// Polls the pending retry, if any, and tries to lock the mutex with `try_lock` once it
+    /// has elapsed.
+    pub(crate) fn poll_lock<G, F>(&mut self, ctx: &mut Context, try_lock: F) -> Poll<G>
+    where
+        F: Fn(&'a M) -> Option<G>,
+    {
+        loop {
+            if let Some(ref mut timeout) = self.timeout {
+                if timeout.as_mut().poll(ctx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+
+            if let Some(guard) = try_lock(self.mutex) {
+                self.timeout = None;
+                return Poll::Ready(guard);
+            }
+
+            self.timeout = Some(::timeout_future(self.delay));
+            self.delay = cmp::min(self.delay * 2, LOCK_RETRY_MAX_DELAY);
+        }
+    }
+}
+
+impl<'a, M: fmt::Debug> fmt::Debug for LockRetry<'a, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LockRetry")
+            .field("mutex", &self.mutex)
+            .field("delay", &self.delay)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use MainContext;
+
+    #[test]
+    fn test_lock() {
+        let mutex = Mutex::new();
+
+        let guard = mutex.lock();
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+
+        let guard = mutex.try_lock();
+        assert!(guard.is_some());
+    }
+
+    #[test]
+    fn test_from_glib_ptr() {
+        let mut raw: glib_sys::GMutex = unsafe { mem::zeroed() };
+
+        unsafe {
+            glib_sys::g_mutex_init(&mut raw);
+            let mutex = Mutex::from_glib_ptr(&mut raw);
+            let _guard = mutex.lock();
+            assert!(!from_glib(glib_sys::g_mutex_trylock(&mut raw)));
+        }
+
+        unsafe {
+            assert!(from_glib(glib_sys::g_mutex_trylock(&mut raw)));
+            glib_sys::g_mutex_unlock(&mut raw);
+            glib_sys::g_mutex_clear(&mut raw);
+        }
+    }
+
+    #[test]
+    fn test_lock_future() {
+        let c = MainContext::new();
+        let mutex = Arc::new(Mutex::new());
+
+        let (locked_sender, locked_receiver) = mpsc::channel();
+        let mutex_clone = mutex.clone();
+        let t = thread::spawn(move || {
+            let _guard = mutex_clone.lock();
+            locked_sender.send(()).unwrap();
+            thread::sleep(Duration::from_millis(50));
+        });
+
+        locked_receiver.recv().unwrap();
+        assert!(mutex.try_lock().is_none());
+
+        let guard = c.block_on(mutex.lock_future());
+        drop(guard);
+
+        t.join().unwrap();
+    }
+}
@@ -6,15 +6,21 @@
 
 use glib_sys;
 use gobject_sys;
+use once_cell::sync::Lazy;
 use quark::Quark;
+use std::any::TypeId;
 use std::cmp;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::hash;
+use std::iter;
 use std::marker::PhantomData;
 use std::mem;
 use std::ops;
 use std::pin::Pin;
 use std::ptr;
+use std::sync::{Arc, Mutex};
 use translate::*;
 use types::StaticType;
 
@@ -34,8 +40,11 @@ pub use gobject_sys::GObject;
 pub use gobject_sys::GObjectClass;
 
 /// Implemented by types representing `glib::Object` and subclasses of it.
+///
+/// This trait can only be implemented via the wrapper macros, see [`IsA`](trait.IsA.html).
 pub unsafe trait ObjectType:
-    UnsafeFrom<ObjectRef>
+    sealed::Sealed<Self>
+    + UnsafeFrom<ObjectRef>
     + Into<ObjectRef>
     + StaticType
     + fmt::Debug
@@ -80,8 +89,25 @@ pub trait UnsafeFrom<T> {
 ///
 ///
 /// The trait can only be implemented if the appropriate `ToGlibPtr`
-/// implementations exist.
-pub unsafe trait IsA<T: ObjectType>: ObjectType + AsRef<T> + 'static {}
+/// implementations exist, and only by the wrapper macros. Manual
+/// implementations fail to compile because the macros also implement a
+/// hidden companion trait, see the compile-fail tests in
+/// `glib-macros/tests/compile-fail`.
+pub unsafe trait IsA<T: ObjectType>:
+    ObjectType + sealed::Sealed<T> + AsRef<T> + 'static
+{
+}
+
+#[doc(hidden)]
+pub mod sealed {
+    /// Companion trait of `ObjectType` and `IsA`.
+    ///
+    /// `Sealed<T>` is implemented by the wrapper macros for the wrapper type itself and for all
+    /// its parent classes and interfaces. It has to be public so that the macros can name it
+    /// from other crates, but it's not part of the public API and must not be implemented
+    /// manually.
+    pub trait Sealed<T: ?Sized> {}
+}
 
 /// Trait for mapping a class struct type to its corresponding instance type.
 pub unsafe trait IsClassFor: Sized + 'static {
@@ -396,6 +422,155 @@ pub trait Cast: ObjectType {
 
 impl<T: ObjectType> Cast for T {}
 
+/// Associated items available on all object wrappers.
+///
+/// These are provided by a trait instead of being generated as inherent items of every wrapper
+/// so that they can't clash with items of the same name of a specific wrapper.
+pub trait ObjectTypeExt: ObjectType {
+    /// `None` of this type, e.g. for passing to functions taking an optional object.
+    const NONE: Option<&'static Self> = None;
+
+    /// Returns the name of the GLib type of this wrapper.
+    ///
+    /// Unlike `Type::name()` this does not allocate, as GLib keeps type names around until the
+    /// end of the process.
+    fn type_name() -> &'static str {
+        unsafe {
+            let name = gobject_sys::g_type_name(Self::static_type().to_glib());
+            CStr::from_ptr(name).to_str().unwrap()
+        }
+    }
+}
+
+impl<T: ObjectType> ObjectTypeExt for T {}
+
+/// Casting support for optional objects.
+///
+/// This allows casting an `Option<T>` directly instead of going through `Option::and_then()`,
+/// e.g. `widget.get_parent().and_downcast::<gtk::Box>()`.
+pub trait OptionCastExt: Sized {
+    /// Object type contained in the `Option`.
+    type Target: ObjectType;
+
+    /// Tries to downcast the contained object to a subclass or interface implementor `U`.
+    ///
+    /// Returns `None` if there is no object or if it is not an instance of `U`.
+    fn and_downcast<U: ObjectType>(self) -> Option<U>
+    where
+        Self::Target: CanDowncast<U>;
+
+    /// Tries to downcast a reference to the contained object to a subclass or interface
+    /// implementor `U`.
+    ///
+    /// Returns `None` if there is no object or if it is not an instance of `U`.
+    fn and_downcast_ref<U: ObjectType>(&self) -> Option<&U>
+    where
+        Self::Target: CanDowncast<U>;
+
+    /// Upcasts the contained object to a superclass or interface `U`.
+    fn and_upcast<U: ObjectType>(self) -> Option<U>
+    where
+        Self::Target: IsA<U>;
+
+    /// Upcasts a reference to the contained object to a superclass or interface `U`.
+    fn and_upcast_ref<U: ObjectType>(&self) -> Option<&U>
+    where
+        Self::Target: IsA<U>;
+
+    /// Tries to cast the contained object to `U`, with all checks performed at runtime.
+    ///
+    /// Returns `None` if there is no object or if it is not an instance of `U`.
+    fn and_dynamic_cast<U: ObjectType>(self) -> Option<U>;
+
+    /// Tries to cast a reference to the contained object to `U`, with all checks performed at
+    /// runtime.
+    ///
+    /// Returns `None` if there is no object or if it is not an instance of `U`.
+    fn and_dynamic_cast_ref<U: ObjectType>(&self) -> Option<&U>;
+}
+
+impl<T: ObjectType> OptionCastExt for Option<T> {
+    type Target = T;
+
+    #[inline]
+    fn and_downcast<U: ObjectType>(self) -> Option<U>
+    where
+        T: CanDowncast<U>,
+    {
+        self.and_then(|obj| obj.downcast().ok())
+    }
+
+    #[inline]
+    fn and_downcast_ref<U: ObjectType>(&self) -> Option<&U>
+    where
+        T: CanDowncast<U>,
+    {
+        self.as_ref().and_then(|obj| obj.downcast_ref())
+    }
+
+    #[inline]
+    fn and_upcast<U: ObjectType>(self) -> Option<U>
+    where
+        T: IsA<U>,
+    {
+        self.map(|obj| obj.upcast())
+    }
+
+    #[inline]
+    fn and_upcast_ref<U: ObjectType>(&self) -> Option<&U>
+    where
+        T: IsA<U>,
+    {
+        self.as_ref().map(|obj| obj.upcast_ref())
+    }
+
+    #[inline]
+    fn and_dynamic_cast<U: ObjectType>(self) -> Option<U> {
+        self.and_then(|obj| obj.dynamic_cast().ok())
+    }
+
+    #[inline]
+    fn and_dynamic_cast_ref<U: ObjectType>(&self) -> Option<&U> {
+        self.as_ref().and_then(|obj| obj.dynamic_cast_ref())
+    }
+}
+
+/// Casting support for iterators over objects.
+///
+/// This allows keeping only the objects of a specific type, e.g.
+/// `container.get_children().into_iter().filter_downcast::<gtk::Button>()`.
+pub trait TryCastExt: Iterator + Sized
+where
+    Self::Item: ObjectType,
+{
+    /// Returns an iterator over all objects that are instances of the subclass or interface
+    /// implementor `U`, downcast to `U`.
+    fn filter_downcast<U: ObjectType>(self) -> iter::FilterMap<Self, fn(Self::Item) -> Option<U>>
+    where
+        Self::Item: CanDowncast<U>,
+    {
+        fn downcast<T: ObjectType + CanDowncast<U>, U: ObjectType>(obj: T) -> Option<U> {
+            obj.downcast().ok()
+        }
+
+        self.filter_map(downcast::<Self::Item, U> as fn(Self::Item) -> Option<U>)
+    }
+
+    /// Returns an iterator over all objects that are instances of `U`, cast to `U` with all
+    /// checks performed at runtime.
+    fn filter_dynamic_cast<U: ObjectType>(
+        self,
+    ) -> iter::FilterMap<Self, fn(Self::Item) -> Option<U>> {
+        fn dynamic_cast<T: ObjectType, U: ObjectType>(obj: T) -> Option<U> {
+            obj.dynamic_cast().ok()
+        }
+
+        self.filter_map(dynamic_cast::<Self::Item, U> as fn(Self::Item) -> Option<U>)
+    }
+}
+
+impl<I: Iterator> TryCastExt for I where I::Item: ObjectType {}
+
 /// Marker trait for the statically known possibility of downcasting from `Self` to `T`.
 pub trait CanDowncast<T> {}
 
@@ -564,6 +739,9 @@ impl FromGlibPtrNone<*mut GObject> for ObjectRef {
         assert!(!ptr.is_null());
         assert_ne!((*ptr).ref_count, 0);
 
+        #[cfg(feature = "debug-object-tracking")]
+        ::debug::track_object(ptr);
+
         // Attention: This takes ownership of floating references!
         ObjectRef {
             inner: ptr::NonNull::new_unchecked(gobject_sys::g_object_ref_sink(ptr)),
@@ -587,6 +765,9 @@ impl FromGlibPtrFull<*mut GObject> for ObjectRef {
         assert!(!ptr.is_null());
         assert_ne!((*ptr).ref_count, 0);
 
+        #[cfg(feature = "debug-object-tracking")]
+        ::debug::track_object(ptr);
+
         ObjectRef {
             inner: ptr::NonNull::new_unchecked(ptr),
         }
@@ -779,6 +960,9 @@ macro_rules! glib_object_wrapper {
             }
         }
 
+        #[doc(hidden)]
+        impl $crate::object::sealed::Sealed<$name> for $name { }
+
         #[doc(hidden)]
         unsafe impl $crate::object::IsA<$name> for $name { }
 
@@ -1093,6 +1277,9 @@ macro_rules! glib_object_wrapper {
     (@munch_impls $name:ident, ) => { };
 
     (@munch_impls $name:ident, $super_name:path) => {
+        #[doc(hidden)]
+        impl $crate::object::sealed::Sealed<$super_name> for $name { }
+
         unsafe impl $crate::object::IsA<$super_name> for $name { }
 
         #[doc(hidden)]
@@ -1203,6 +1390,9 @@ macro_rules! glib_object_wrapper {
             }
         }
 
+        #[doc(hidden)]
+        impl $crate::object::sealed::Sealed<$crate::object::Object> for $name { }
+
         #[doc(hidden)]
         unsafe impl $crate::object::IsA<$crate::object::Object> for $name { }
     };
@@ -1219,6 +1409,9 @@ macro_rules! glib_object_wrapper {
             }
         }
 
+        #[doc(hidden)]
+        impl $crate::object::sealed::Sealed<$crate::object::Object> for $name { }
+
         #[doc(hidden)]
         unsafe impl $crate::object::IsA<$crate::object::Object> for $name { }
     };
@@ -1231,8 +1424,6 @@ glib_object_wrapper!(@object
 
 impl Object {
     pub fn new(type_: Type, properties: &[(&str, &dyn ToValue)]) -> Result<Object, BoolError> {
-        use std::ffi::CString;
-
         let klass = ObjectClass::from_type(type_)
             .ok_or_else(|| glib_bool_error!("Can't retrieve class for type '{}'", type_))?;
         let pspecs = klass.list_properties();
@@ -1249,7 +1440,8 @@ impl Object {
 
                 let mut value = value.to_value();
                 validate_property_type(type_, true, &pspec, &mut value)?;
-                Ok((CString::new(*name).unwrap(), value))
+                // This is the name of an existing property, so it can be cached
+                Ok((cached_cstring(name), value))
             })
             .collect::<Result<smallvec::SmallVec<[_; 10]>, _>>()?;
 
@@ -1257,8 +1449,6 @@ impl Object {
     }
 
     pub fn new_generic(type_: Type, properties: &[(&str, Value)]) -> Result<Object, BoolError> {
-        use std::ffi::CString;
-
         let klass = ObjectClass::from_type(type_)
             .ok_or_else(|| glib_bool_error!("Can't retrieve class for type '{}'", type_))?;
         let pspecs = klass.list_properties();
@@ -1275,7 +1465,8 @@ impl Object {
 
                 let mut value = value.clone();
                 validate_property_type(type_, true, &pspec, &mut value)?;
-                Ok((CString::new(*name).unwrap(), value))
+                // This is the name of an existing property, so it can be cached
+                Ok((cached_cstring(name), value))
             })
             .collect::<Result<smallvec::SmallVec<[_; 10]>, _>>()?;
 
@@ -1284,7 +1475,7 @@ impl Object {
 
     unsafe fn new_internal(
         type_: Type,
-        params: &[(std::ffi::CString, Value)],
+        params: &[(&'static std::ffi::CStr, Value)],
     ) -> Result<Object, BoolError> {
         if !type_.is_a(&Object::static_type()) {
             return Err(glib_bool_error!(
@@ -1335,6 +1526,19 @@ impl Object {
     }
 }
 
+// Returns the quark under which values of type `T` are stored by `ObjectExt::set_data_typed()`.
+fn typed_data_quark<T: 'static>() -> Quark {
+    static QUARKS: Lazy<Mutex<HashMap<TypeId, Quark>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+    *QUARKS
+        .lock()
+        .unwrap()
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| {
+            Quark::from_string(&format!("glib-rs-typed-data-{:?}", TypeId::of::<T>()))
+        })
+}
+
 pub trait ObjectExt: ObjectType {
     /// Returns `true` if the object is an instance of (can be cast to) `T`.
     fn is<T: StaticType>(&self) -> bool;
@@ -1390,10 +1594,36 @@ pub trait ObjectExt: ObjectType {
     /// The caller is responsible for ensuring the returned value is of a suitable type
     unsafe fn steal_data<QD: 'static>(&self, key: &str) -> Option<QD>;
 
-    fn block_signal(&self, handler_id: &SignalHandlerId);
-    fn unblock_signal(&self, handler_id: &SignalHandlerId);
+    /// Attaches `value` to the object, keyed by its type `QD`.
+    ///
+    /// This replaces any value of the same type that was previously attached.
+    fn set_data_typed<QD: Send + Sync + 'static>(&self, value: QD);
+
+    /// Returns the value of type `QD` attached to the object, if any.
+    fn get_data_typed<QD: Send + Sync + 'static>(&self) -> Option<Arc<QD>>;
+
+    /// Removes the value of type `QD` attached to the object and returns it, if any.
+    fn steal_data_typed<QD: Send + Sync + 'static>(&self) -> Option<Arc<QD>>;
+
+    fn block_signal(&self, handler_id: SignalHandlerId);
+    fn unblock_signal(&self, handler_id: SignalHandlerId);
     fn stop_signal_emission(&self, signal_name: &str);
 
+    /// Returns `true` if there are handlers connected to `signal_name` that would be called on
+    /// emission.
+    ///
+    /// `detail` overrides a detail given as part of `signal_name`, e.g. `notify::name`. This can
+    /// be used to skip constructing expensive signal arguments if nobody is listening.
+    fn signal_has_handler_pending<'a, N: Into<&'a str>>(
+        &self,
+        signal_name: N,
+        detail: Option<Quark>,
+        may_be_blocked: bool,
+    ) -> Result<bool, BoolError>;
+
+    /// Returns `true` if `handler_id` is still connected to this object.
+    fn handler_is_connected(&self, handler_id: SignalHandlerId) -> bool;
+
     fn connect<'a, N, F>(
         &self,
         signal_name: N,
@@ -1434,6 +1664,28 @@ pub trait ObjectExt: ObjectType {
     ) -> Result<Option<Value>, BoolError>;
     fn disconnect(&self, handler_id: SignalHandlerId);
 
+    /// Disconnects all handlers of `signal_name` and returns how many were disconnected.
+    ///
+    /// If `signal_name` contains a detail, e.g. `notify::name`, only handlers connected with
+    /// that detail are disconnected. Note that this also disconnects handlers that were not
+    /// connected through the bindings, e.g. by C code.
+    fn disconnect_signal_handlers<'a, N: Into<&'a str>>(
+        &self,
+        signal_name: N,
+    ) -> Result<u32, BoolError>;
+
+    /// Starts tracking the handlers connected to this object through the bindings, so that they
+    /// can be disconnected with [`disconnect_all`](#tymethod.disconnect_all).
+    ///
+    /// Tracking is off by default to keep connecting signal handlers cheap. Handlers connected
+    /// before this call are not tracked.
+    fn track_signal_handlers(&self);
+
+    /// Disconnects all handlers that were connected to this object through the bindings since
+    /// [`track_signal_handlers`](#tymethod.track_signal_handlers) was called, and returns how
+    /// many were disconnected.
+    fn disconnect_all(&self) -> u32;
+
     fn connect_notify<F: Fn(&Self, &::ParamSpec) + Send + Sync + 'static>(
         &self,
         name: Option<&str>,
@@ -1458,11 +1710,44 @@ pub trait ObjectExt: ObjectType {
     ) -> BindingBuilder<'a>;
 
     fn ref_count(&self) -> u32;
+
+    /// Returns `true` if `self` and `other` are the same object instance.
+    ///
+    /// This is the same as comparing with `==` but also works between references of different
+    /// types, e.g. a subclass and one of its parent classes.
+    fn ptr_eq<U: ObjectType>(&self, other: &U) -> bool;
+
+    /// Returns an identifier of the object instance, e.g. for correlating log messages about
+    /// the same instance.
+    ///
+    /// The identifier stays the same for the whole lifetime of the instance. It is derived from
+    /// the address of the instance but hashed with a random per-process key, so it does not
+    /// reveal the address. Once the instance is finalized the identifier might be reused.
+    fn object_id(&self) -> u64;
+
+    /// Returns the interfaces implemented by the type of the object.
+    fn interfaces(&self) -> Vec<Type>;
+
+    /// Returns `true` if `I` is an interface that is implemented by the type of the object.
+    fn implements<I: StaticType>(&self) -> bool;
 }
 
 impl<T: ObjectType> ObjectExt for T {
+    #[inline]
     fn is<U: StaticType>(&self) -> bool {
-        self.get_type().is_a(&U::static_type())
+        // Checks the instance directly instead of going through its class. GLib has a fast path
+        // for exact type matches here and only walks the type hierarchy otherwise.
+        //
+        // `U::static_type()` is not cached here: a `static` in a generic function is shared by
+        // all instantiations, so a per-type cache would need a lookup table that is slower than
+        // the call itself. The wrapper macros and `glib_object_subclass!` already only register
+        // the type once and return the stored `GType` afterwards.
+        unsafe {
+            from_glib(gobject_sys::g_type_check_instance_is_a(
+                self.as_object_ref().to_glib_none().0 as *mut gobject_sys::GTypeInstance,
+                U::static_type().to_glib(),
+            ))
+        }
     }
 
     fn get_type(&self) -> Type {
@@ -1660,9 +1945,11 @@ impl<T: ObjectType> ObjectExt for T {
 
     unsafe fn set_qdata<QD: 'static>(&self, key: Quark, value: QD) {
         unsafe extern "C" fn drop_value<QD>(ptr: glib_sys::gpointer) {
-            debug_assert!(!ptr.is_null());
-            let value: Box<QD> = Box::from_raw(ptr as *mut QD);
-            drop(value)
+            ::guard_ffi(|| {
+                debug_assert!(!ptr.is_null());
+                let value: Box<QD> = Box::from_raw(ptr as *mut QD);
+                drop(value)
+            })
         }
 
         let ptr = Box::into_raw(Box::new(value)) as glib_sys::gpointer;
@@ -1707,7 +1994,73 @@ impl<T: ObjectType> ObjectExt for T {
         self.steal_qdata::<QD>(Quark::from_string(key))
     }
 
-    fn block_signal(&self, handler_id: &SignalHandlerId) {
+    fn set_data_typed<QD: Send + Sync + 'static>(&self, value: QD) {
+        unsafe extern "C" fn drop_value<QD>(ptr: glib_sys::gpointer) {
+            ::guard_ffi(|| {
+                debug_assert!(!ptr.is_null());
+                drop(Arc::from_raw(ptr as *const QD));
+            })
+        }
+
+        unsafe {
+            let ptr = Arc::into_raw(Arc::new(value)) as glib_sys::gpointer;
+            gobject_sys::g_object_set_qdata_full(
+                self.as_object_ref().to_glib_none().0,
+                typed_data_quark::<QD>().to_glib(),
+                ptr,
+                Some(drop_value::<QD>),
+            );
+        }
+    }
+
+    fn get_data_typed<QD: Send + Sync + 'static>(&self) -> Option<Arc<QD>> {
+        // Takes a new reference while the object's data is locked so that the value can't be
+        // freed concurrently by another thread replacing or stealing it.
+        unsafe extern "C" fn dup_value<QD>(
+            ptr: glib_sys::gpointer,
+            _user_data: glib_sys::gpointer,
+        ) -> glib_sys::gpointer {
+            ::guard_ffi(|| {
+                if ptr.is_null() {
+                    return ptr;
+                }
+                let value = Arc::from_raw(ptr as *const QD);
+                let new_value = Arc::clone(&value);
+                mem::forget(value);
+                Arc::into_raw(new_value) as glib_sys::gpointer
+            })
+        }
+
+        unsafe {
+            let ptr = gobject_sys::g_object_dup_qdata(
+                self.as_object_ref().to_glib_none().0,
+                typed_data_quark::<QD>().to_glib(),
+                Some(dup_value::<QD>),
+                ptr::null_mut(),
+            );
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Arc::from_raw(ptr as *const QD))
+            }
+        }
+    }
+
+    fn steal_data_typed<QD: Send + Sync + 'static>(&self) -> Option<Arc<QD>> {
+        unsafe {
+            let ptr = gobject_sys::g_object_steal_qdata(
+                self.as_object_ref().to_glib_none().0,
+                typed_data_quark::<QD>().to_glib(),
+            );
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Arc::from_raw(ptr as *const QD))
+            }
+        }
+    }
+
+    fn block_signal(&self, handler_id: SignalHandlerId) {
         unsafe {
             gobject_sys::g_signal_handler_block(
                 self.as_object_ref().to_glib_none().0,
@@ -1716,7 +2069,7 @@ impl<T: ObjectType> ObjectExt for T {
         }
     }
 
-    fn unblock_signal(&self, handler_id: &SignalHandlerId) {
+    fn unblock_signal(&self, handler_id: SignalHandlerId) {
         unsafe {
             gobject_sys::g_signal_handler_unblock(
                 self.as_object_ref().to_glib_none().0,
@@ -1734,12 +2087,136 @@ impl<T: ObjectType> ObjectExt for T {
         }
     }
 
+    fn signal_has_handler_pending<'a, N: Into<&'a str>>(
+        &self,
+        signal_name: N,
+        detail: Option<Quark>,
+        may_be_blocked: bool,
+    ) -> Result<bool, BoolError> {
+        let signal_name: &str = signal_name.into();
+        let type_ = self.get_type();
+
+        unsafe {
+            let mut signal_id = 0;
+            let mut signal_detail = 0;
+
+            let found: bool = from_glib(gobject_sys::g_signal_parse_name(
+                signal_name.to_glib_none().0,
+                type_.to_glib(),
+                &mut signal_id,
+                &mut signal_detail,
+                true.to_glib(),
+            ));
+
+            if !found {
+                return Err(glib_bool_error!(
+                    "Signal '{}' of type '{}' not found",
+                    signal_name,
+                    type_
+                ));
+            }
+
+            if let Some(detail) = detail {
+                signal_detail = detail.to_glib();
+            }
+
+            Ok(from_glib(gobject_sys::g_signal_has_handler_pending(
+                self.as_object_ref().to_glib_none().0,
+                signal_id,
+                signal_detail,
+                may_be_blocked.to_glib(),
+            )))
+        }
+    }
+
+    fn handler_is_connected(&self, handler_id: SignalHandlerId) -> bool {
+        unsafe {
+            from_glib(gobject_sys::g_signal_handler_is_connected(
+                self.as_object_ref().to_glib_none().0,
+                handler_id.to_glib(),
+            ))
+        }
+    }
+
     fn disconnect(&self, handler_id: SignalHandlerId) {
         unsafe {
             gobject_sys::g_signal_handler_disconnect(
                 self.as_object_ref().to_glib_none().0,
                 handler_id.to_glib(),
             );
+            ::signal::untrack_handler(self.as_object_ref().to_glib_none().0, handler_id.to_glib());
+        }
+    }
+
+    fn disconnect_signal_handlers<'a, N: Into<&'a str>>(
+        &self,
+        signal_name: N,
+    ) -> Result<u32, BoolError> {
+        let signal_name: &str = signal_name.into();
+        let type_ = self.get_type();
+
+        unsafe {
+            let mut signal_id = 0;
+            let mut signal_detail = 0;
+
+            let found: bool = from_glib(gobject_sys::g_signal_parse_name(
+                signal_name.to_glib_none().0,
+                type_.to_glib(),
+                &mut signal_id,
+                &mut signal_detail,
+                true.to_glib(),
+            ));
+
+            if !found {
+                return Err(glib_bool_error!(
+                    "Signal '{}' of type '{}' not found",
+                    signal_name,
+                    type_
+                ));
+            }
+
+            let mut mask = gobject_sys::G_SIGNAL_MATCH_ID;
+            if signal_detail != 0 {
+                mask |= gobject_sys::G_SIGNAL_MATCH_DETAIL;
+            }
+
+            let count = gobject_sys::g_signal_handlers_disconnect_matched(
+                self.as_object_ref().to_glib_none().0,
+                mask,
+                signal_id,
+                signal_detail,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if count > 0 {
+                ::signal::untrack_disconnected_handlers(self.as_object_ref().to_glib_none().0);
+            }
+
+            Ok(count)
+        }
+    }
+
+    fn track_signal_handlers(&self) {
+        unsafe {
+            ::signal::enable_handler_tracking(self.as_object_ref().to_glib_none().0);
+        }
+    }
+
+    fn disconnect_all(&self) -> u32 {
+        unsafe {
+            let instance = self.as_object_ref().to_glib_none().0;
+            let mut count = 0;
+            for handler in ::signal::take_tracked_handlers(instance) {
+                let connected: bool = from_glib(gobject_sys::g_signal_handler_is_connected(
+                    instance, handler,
+                ));
+                if connected {
+                    gobject_sys::g_signal_handler_disconnect(instance, handler);
+                    count += 1;
+                }
+            }
+            count
         }
     }
 
@@ -1763,11 +2240,13 @@ impl<T: ObjectType> ObjectExt for T {
         ) where
             P: ObjectType,
         {
-            let f: &F = &*(f as *const F);
-            f(
-                Object::from_glib_borrow(this).unsafe_cast_ref(),
-                &from_glib_borrow(param_spec),
-            )
+            ::guard_ffi(|| {
+                let f: &F = &*(f as *const F);
+                f(
+                    Object::from_glib_borrow(this).unsafe_cast_ref(),
+                    &from_glib_borrow(param_spec),
+                )
+            })
         }
 
         let signal_name = if let Some(name) = name {
@@ -1986,6 +2465,7 @@ impl<T: ObjectType> ObjectExt for T {
                 type_
             ))
         } else {
+            ::signal::track_handler(self.as_object_ref().to_glib_none().0, handler);
             Ok(from_glib(handler))
         }
     }
@@ -2000,7 +2480,7 @@ impl<T: ObjectType> ObjectExt for T {
             let type_ = self.get_type();
 
             let self_v = {
-                let mut v = Value::uninitialized();
+                let mut v = Value::new_empty();
                 gobject_sys::g_value_init(v.to_glib_none_mut().0, self.get_type().to_glib());
                 gobject_sys::g_value_set_object(
                     v.to_glib_none_mut().0,
@@ -2018,7 +2498,7 @@ impl<T: ObjectType> ObjectExt for T {
             let (signal_id, signal_detail, return_type) =
                 validate_signal_arguments(type_, signal_name, &mut args[1..])?;
 
-            let mut return_value = Value::uninitialized();
+            let mut return_value = Value::new_empty();
             if return_type != Type::Unit {
                 gobject_sys::g_value_init(return_value.to_glib_none_mut().0, return_type.to_glib());
             }
@@ -2048,7 +2528,7 @@ impl<T: ObjectType> ObjectExt for T {
             let type_ = self.get_type();
 
             let self_v = {
-                let mut v = Value::uninitialized();
+                let mut v = Value::new_empty();
                 gobject_sys::g_value_init(v.to_glib_none_mut().0, self.get_type().to_glib());
                 gobject_sys::g_value_set_object(
                     v.to_glib_none_mut().0,
@@ -2063,7 +2543,7 @@ impl<T: ObjectType> ObjectExt for T {
             let (signal_id, signal_detail, return_type) =
                 validate_signal_arguments(type_, signal_name, &mut args[1..])?;
 
-            let mut return_value = Value::uninitialized();
+            let mut return_value = Value::new_empty();
             if return_type != Type::Unit {
                 gobject_sys::g_value_init(return_value.to_glib_none_mut().0, return_type.to_glib());
             }
@@ -2085,11 +2565,9 @@ impl<T: ObjectType> ObjectExt for T {
 
     fn downgrade(&self) -> WeakRef<T> {
         unsafe {
-            let w = WeakRef(Box::pin(mem::zeroed()), PhantomData);
-            gobject_sys::g_weak_ref_init(
-                mut_override(&*w.0),
-                self.as_object_ref().to_glib_none().0,
-            );
+            let ptr = self.as_object_ref().to_glib_none().0;
+            let w = WeakRef(Box::pin(mem::zeroed()), ptr as usize, PhantomData);
+            gobject_sys::g_weak_ref_init(mut_override(&*w.0), ptr);
             w
         }
     }
@@ -2112,6 +2590,32 @@ impl<T: ObjectType> ObjectExt for T {
 
         unsafe { glib_sys::g_atomic_int_get(&(*ptr).ref_count as *const u32 as *const i32) as u32 }
     }
+
+    fn ptr_eq<U: ObjectType>(&self, other: &U) -> bool {
+        let ptr: *mut gobject_sys::GObject = self.as_object_ref().to_glib_none().0;
+        let other_ptr: *mut gobject_sys::GObject = other.as_object_ref().to_glib_none().0;
+        ptr == other_ptr
+    }
+
+    fn object_id(&self) -> u64 {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hasher};
+
+        static OBJECT_ID_KEY: Lazy<RandomState> = Lazy::new(RandomState::new);
+
+        let ptr: *mut gobject_sys::GObject = self.as_object_ref().to_glib_none().0;
+        let mut hasher = OBJECT_ID_KEY.build_hasher();
+        hasher.write_usize(ptr as usize);
+        hasher.finish()
+    }
+
+    fn interfaces(&self) -> Vec<Type> {
+        self.get_object_class().interfaces()
+    }
+
+    fn implements<I: StaticType>(&self) -> bool {
+        I::static_type().is_a(&Type::BaseInterface) && self.is::<I>()
+    }
 }
 
 // Validate that the given property value has an acceptable type for the given property pspec
@@ -2204,14 +2708,28 @@ fn validate_signal_arguments(
     let mut signal_id = 0;
     let mut signal_detail = 0;
 
-    let found: bool = unsafe {
-        from_glib(gobject_sys::g_signal_parse_name(
-            signal_name.to_glib_none().0,
+    let mut parse_name = |name: &CStr| unsafe {
+        let found: bool = from_glib(gobject_sys::g_signal_parse_name(
+            name.as_ptr(),
             type_.to_glib(),
             &mut signal_id,
             &mut signal_detail,
             true.to_glib(),
-        ))
+        ));
+        if found {
+            Some(())
+        } else {
+            None
+        }
+    };
+
+    // Any detail is accepted for signals, so only cache plain signal names to not grow the
+    // cache without bounds.
+    let found = if signal_name.contains("::") {
+        let name = CString::new(signal_name).expect("String contains NUL bytes");
+        parse_name(&name).is_some()
+    } else {
+        with_cached_cstring(signal_name, parse_name).is_some()
     };
 
     if !found {
@@ -2319,13 +2837,20 @@ impl ObjectClass {
         unsafe {
             let klass = self as *const _ as *const gobject_sys::GObjectClass;
 
-            from_glib_none(gobject_sys::g_object_class_find_property(
-                klass as *mut _,
-                property_name.to_glib_none().0,
-            ))
+            with_cached_cstring(property_name, |name| {
+                from_glib_none(gobject_sys::g_object_class_find_property(
+                    klass as *mut _,
+                    name.as_ptr(),
+                ))
+            })
         }
     }
 
+    /// Returns the interfaces implemented by the type of this class.
+    pub fn interfaces(&self) -> Vec<Type> {
+        self.get_type().interfaces()
+    }
+
     pub fn list_properties(&self) -> Vec<::ParamSpec> {
         unsafe {
             let klass = self as *const _ as *const gobject_sys::GObjectClass;
@@ -2348,12 +2873,12 @@ glib_wrapper! {
 }
 
 #[derive(Debug)]
-pub struct WeakRef<T: ObjectType>(Pin<Box<gobject_sys::GWeakRef>>, PhantomData<*mut T>);
+pub struct WeakRef<T: ObjectType>(Pin<Box<gobject_sys::GWeakRef>>, usize, PhantomData<*mut T>);
 
 impl<T: ObjectType> WeakRef<T> {
     pub fn new() -> WeakRef<T> {
         unsafe {
-            let mut w = WeakRef(Box::pin(mem::zeroed()), PhantomData);
+            let mut w = WeakRef(Box::pin(mem::zeroed()), 0, PhantomData);
             gobject_sys::g_weak_ref_init(
                 Pin::as_mut(&mut w.0).get_unchecked_mut(),
                 ptr::null_mut(),
@@ -2373,6 +2898,36 @@ impl<T: ObjectType> WeakRef<T> {
             }
         }
     }
+
+    /// Upgrades the weak reference and calls `func` with the object if it is
+    /// still alive.
+    ///
+    /// Returns `None` if the object was already destroyed.
+    pub fn upgrade_map<R, F: FnOnce(&T) -> R>(&self, func: F) -> Option<R> {
+        self.upgrade().map(|obj| func(&obj))
+    }
+}
+
+/// Compares the addresses of the objects the weak references were created for.
+///
+/// The address is stored when the weak reference is created and does not change once the
+/// object is destroyed, so weak references can be stored in hash-based collections and removed
+/// again later. Note that the address of a destroyed object can be reused by a new object.
+impl<T: ObjectType> PartialEq for WeakRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+
+impl<T: ObjectType> Eq for WeakRef<T> {}
+
+/// Hashes the address of the object the weak reference was created for.
+///
+/// See the `PartialEq` implementation for details.
+impl<T: ObjectType> hash::Hash for WeakRef<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.1.hash(state)
+    }
 }
 
 impl<T: ObjectType> Drop for WeakRef<T> {
@@ -2388,7 +2943,7 @@ impl<T: ObjectType> Clone for WeakRef<T> {
         unsafe {
             let o = self.upgrade();
 
-            let mut c = WeakRef(Box::pin(mem::zeroed()), PhantomData);
+            let mut c = WeakRef(Box::pin(mem::zeroed()), self.1, PhantomData);
             gobject_sys::g_weak_ref_init(
                 Pin::as_mut(&mut c.0).get_unchecked_mut(),
                 o.to_glib_none().0 as *mut gobject_sys::GObject,
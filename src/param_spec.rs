@@ -413,6 +413,56 @@ impl ParamSpec {
         }
     }
 
+    /// Creates a `ParamSpec` that overrides the property `name` of the class `class_type`.
+    ///
+    /// This can be used in subclasses to override properties inherited from a parent class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class_type` has no property `name`.
+    pub fn override_for_class(name: &str, class_type: ::Type) -> ParamSpec {
+        unsafe {
+            let klass = gobject_sys::g_type_class_ref(class_type.to_glib());
+            let overridden = gobject_sys::g_object_class_find_property(
+                klass as *mut gobject_sys::GObjectClass,
+                name.to_glib_none().0,
+            );
+            gobject_sys::g_type_class_unref(klass);
+            assert!(
+                !overridden.is_null(),
+                "Class '{}' has no property '{}'",
+                class_type,
+                name
+            );
+            let overridden: Borrowed<ParamSpec> = from_glib_borrow(overridden);
+            ParamSpec::override_(name, &overridden)
+        }
+    }
+
+    /// Creates a `ParamSpec` that overrides the property `name` of the interface `iface_type`.
+    ///
+    /// This can be used in subclasses to implement the properties of an interface.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iface_type` has no property `name`.
+    pub fn override_for_interface(name: &str, iface_type: ::Type) -> ParamSpec {
+        unsafe {
+            let iface = gobject_sys::g_type_default_interface_ref(iface_type.to_glib());
+            let overridden =
+                gobject_sys::g_object_interface_find_property(iface, name.to_glib_none().0);
+            gobject_sys::g_type_default_interface_unref(iface);
+            assert!(
+                !overridden.is_null(),
+                "Interface '{}' has no property '{}'",
+                iface_type,
+                name
+            );
+            let overridden: Borrowed<ParamSpec> = from_glib_borrow(overridden);
+            ParamSpec::override_(name, &overridden)
+        }
+    }
+
     pub fn param(
         name: &str,
         nick: &str,
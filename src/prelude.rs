@@ -1,6 +1,9 @@
 //! Traits and essential types intended for blanket imports.
 
 pub use {
-    Cast, Continue, IsA, IsClassFor, ObjectExt, ObjectType, ParamSpecType, StaticType,
-    StaticVariantType, ToSendValue, ToValue, ToVariant,
+    Cast, Continue, IsA, IsClassFor, ObjectExt, ObjectType, ObjectTypeExt, OptionCastExt,
+    ParamSpecType, StaticType, StaticVariantType, ToSendValue, ToValue, ToVariant, TryCastExt,
 };
+
+pub use gobject::auto::traits::*;
+pub use gobject::{TypeModuleExt, TypePluginExt};
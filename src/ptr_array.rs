@@ -0,0 +1,419 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Typed wrapper around `GPtrArray`.
+//!
+//! # Examples
+//!
+//! ```
+//! use glib::prelude::*;
+//! use glib::{Object, PtrArray};
+//!
+//! let mut a = PtrArray::new();
+//! a.push(Object::new(Object::static_type(), &[]).unwrap());
+//! a.push(Object::new(Object::static_type(), &[]).unwrap());
+//! assert_eq!(a.len(), 2);
+//! for obj in a.iter() {
+//!     assert_eq!(obj.get_type(), Object::static_type());
+//! }
+//! ```
+
+use glib_sys;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use translate::*;
+use types::{StaticType, Type};
+
+/// A growable array of GLib pointer types backed by a `GPtrArray`.
+///
+/// Each element is stored as a full reference, i.e. the array owns its elements and releases
+/// them through the element free function when they are removed or the array is freed.
+pub struct PtrArray<T: GlibPtrDefault + 'static>(NonNull<glib_sys::GPtrArray>, PhantomData<T>);
+
+unsafe impl<T: GlibPtrDefault + Send + 'static> Send for PtrArray<T> {}
+unsafe impl<T: GlibPtrDefault + Sync + 'static> Sync for PtrArray<T> {}
+
+unsafe extern "C" fn free_func<T>(ptr: glib_sys::gpointer)
+where
+    T: GlibPtrDefault + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>,
+{
+    ::guard_ffi(|| {
+        if !ptr.is_null() {
+            let ptr: <T as GlibPtrDefault>::GlibType = Ptr::from(ptr);
+            let _: T = from_glib_full(ptr);
+        }
+    })
+}
+
+impl<T> PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'a> ToGlibPtr<'a, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    /// Creates an empty array that releases its elements with `T`'s own free function.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        unsafe {
+            from_glib_full(glib_sys::g_ptr_array_new_full(
+                capacity as u32,
+                Some(free_func::<T>),
+            ))
+        }
+    }
+
+    /// Creates an empty array that releases its elements with `free_func`.
+    ///
+    /// # Safety
+    ///
+    /// `free_func` must release exactly the reference that `ToGlibPtr::to_glib_full()` of `T`
+    /// hands out, otherwise elements are leaked or freed twice.
+    pub unsafe fn with_free_func(free_func: glib_sys::GDestroyNotify) -> Self {
+        from_glib_full(glib_sys::g_ptr_array_new_with_free_func(free_func))
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { (*self.0.as_ptr()).len as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: T) {
+        unsafe {
+            glib_sys::g_ptr_array_add(self.0.as_ptr(), Ptr::to(value.to_glib_full()));
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "index out of bounds");
+        unsafe {
+            glib_sys::g_ptr_array_insert(
+                self.0.as_ptr(),
+                index as i32,
+                Ptr::to(value.to_glib_full()),
+            );
+        }
+    }
+
+    fn get_raw(&self, index: usize) -> <T as GlibPtrDefault>::GlibType {
+        unsafe { Ptr::from(*(*self.0.as_ptr()).pdata.add(index)) }
+    }
+
+    /// Returns a borrowed wrapper for the element at `index`.
+    pub fn get(&self, index: usize) -> Option<Borrowed<T>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        unsafe { Some(from_glib_borrow(self.get_raw(index))) }
+    }
+
+    /// Removes and returns the element at `index`, shifting all elements after it.
+    pub fn remove_index(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        unsafe {
+            let value = from_glib_none(self.get_raw(index));
+            glib_sys::g_ptr_array_remove_index(self.0.as_ptr(), index as u32);
+            value
+        }
+    }
+
+    /// Removes and returns the element at `index`, replacing it with the last element.
+    pub fn remove_index_fast(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "index out of bounds");
+        unsafe {
+            let value = from_glib_none(self.get_raw(index));
+            glib_sys::g_ptr_array_remove_index_fast(self.0.as_ptr(), index as u32);
+            value
+        }
+    }
+
+    /// Removes all elements, releasing them.
+    pub fn clear(&mut self) {
+        unsafe {
+            glib_sys::g_ptr_array_set_size(self.0.as_ptr(), 0);
+        }
+    }
+
+    pub fn iter(&self) -> PtrArrayIter<T> {
+        PtrArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        unsafe {
+            (0..self.len())
+                .map(|i| from_glib_none(self.get_raw(i)))
+                .collect()
+        }
+    }
+}
+
+/// Iterator over borrowed elements of a [`PtrArray`](struct.PtrArray.html).
+pub struct PtrArrayIter<'a, T: GlibPtrDefault + 'static> {
+    array: &'a PtrArray<T>,
+    index: usize,
+}
+
+impl<'a, T> Iterator for PtrArrayIter<'a, T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'b> ToGlibPtr<'b, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    type Item = Borrowed<T>;
+
+    fn next(&mut self) -> Option<Borrowed<T>> {
+        let item = self.array.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for PtrArrayIter<'a, T> where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'b> ToGlibPtr<'b, <T as GlibPtrDefault>::GlibType>
+        + 'static
+{
+}
+
+impl<'a, T> IntoIterator for &'a PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'b> ToGlibPtr<'b, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    type Item = Borrowed<T>;
+    type IntoIter = PtrArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Default for PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'a> ToGlibPtr<'a, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Extend<T> for PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'a> ToGlibPtr<'a, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'a> ToGlibPtr<'a, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut array = Self::with_capacity(iter.size_hint().0);
+        array.extend(iter);
+        array
+    }
+}
+
+impl<T: GlibPtrDefault + 'static> Drop for PtrArray<T> {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_ptr_array_unref(self.0.as_ptr());
+        }
+    }
+}
+
+impl<T: GlibPtrDefault + 'static> fmt::Debug for PtrArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PtrArray")
+            .field("ptr", &self.0)
+            .field("len", unsafe { &(*self.0.as_ptr()).len })
+            .finish()
+    }
+}
+
+impl<T: GlibPtrDefault + 'static> StaticType for PtrArray<T> {
+    fn static_type() -> Type {
+        unsafe { from_glib(glib_sys::g_ptr_array_get_type()) }
+    }
+}
+
+#[doc(hidden)]
+impl<T: GlibPtrDefault + 'static> GlibPtrDefault for PtrArray<T> {
+    type GlibType = *mut glib_sys::GPtrArray;
+}
+
+#[doc(hidden)]
+impl<'a, T: GlibPtrDefault + 'static> ToGlibPtr<'a, *mut glib_sys::GPtrArray> for PtrArray<T> {
+    type Storage = &'a Self;
+
+    #[inline]
+    fn to_glib_none(&'a self) -> Stash<'a, *mut glib_sys::GPtrArray, Self> {
+        Stash(self.0.as_ptr(), self)
+    }
+
+    /// Adds a reference to the `GPtrArray`.
+    ///
+    /// The `PtrArray` must not be mutated while the returned reference is in use.
+    #[inline]
+    fn to_glib_full(&self) -> *mut glib_sys::GPtrArray {
+        unsafe { glib_sys::g_ptr_array_ref(self.0.as_ptr()) }
+    }
+}
+
+#[doc(hidden)]
+impl<'a, T: GlibPtrDefault + 'static> ToGlibPtrMut<'a, *mut glib_sys::GPtrArray> for PtrArray<T> {
+    type Storage = &'a mut Self;
+
+    #[inline]
+    fn to_glib_none_mut(&'a mut self) -> StashMut<'a, *mut glib_sys::GPtrArray, Self> {
+        StashMut(self.0.as_ptr(), self)
+    }
+}
+
+#[doc(hidden)]
+impl<T: GlibPtrDefault + 'static> FromGlibPtrFull<*mut glib_sys::GPtrArray> for PtrArray<T> {
+    /// Takes ownership of the `GPtrArray`.
+    ///
+    /// The array's free function, if any, is kept as is and must match the ownership of `T`.
+    #[inline]
+    unsafe fn from_glib_full(ptr: *mut glib_sys::GPtrArray) -> Self {
+        assert!(!ptr.is_null());
+        PtrArray(NonNull::new_unchecked(ptr), PhantomData)
+    }
+}
+
+#[doc(hidden)]
+impl<T> FromGlibPtrNone<*mut glib_sys::GPtrArray> for PtrArray<T>
+where
+    T: GlibPtrDefault
+        + FromGlibPtrFull<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+        + FromGlibPtrBorrow<<T as GlibPtrDefault>::GlibType>
+        + for<'a> ToGlibPtr<'a, <T as GlibPtrDefault>::GlibType>
+        + 'static,
+{
+    /// Copies the `GPtrArray`, taking a new reference to every element.
+    #[inline]
+    unsafe fn from_glib_none(ptr: *mut glib_sys::GPtrArray) -> Self {
+        assert!(!ptr.is_null());
+        let len = (*ptr).len as usize;
+        let mut array = Self::with_capacity(len);
+        for i in 0..len {
+            let item_ptr: <T as GlibPtrDefault>::GlibType = Ptr::from(*(*ptr).pdata.add(i));
+            array.push(from_glib_none(item_ptr));
+        }
+        array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::ObjectExt;
+    use Object;
+
+    fn new_object() -> Object {
+        Object::new(Object::static_type(), &[]).unwrap()
+    }
+
+    #[test]
+    fn various() {
+        let obj = new_object();
+
+        let mut a = PtrArray::new();
+        a.push(obj.clone());
+        a.push(new_object());
+        a.insert(0, new_object());
+        assert_eq!(a.len(), 3);
+        assert_eq!(obj.ref_count(), 2);
+        assert_eq!(*a.get(1).unwrap(), obj);
+        assert!(a.get(3).is_none());
+        assert_eq!(a.iter().count(), 3);
+
+        let removed = a.remove_index(1);
+        assert_eq!(removed, obj);
+        assert_eq!(a.len(), 2);
+        assert_eq!(obj.ref_count(), 2);
+        drop(removed);
+        assert_eq!(obj.ref_count(), 1);
+
+        a.push(obj.clone());
+        assert_eq!(obj.ref_count(), 2);
+        a.clear();
+        assert!(a.is_empty());
+        assert_eq!(obj.ref_count(), 1);
+
+        a.push(obj.clone());
+        drop(a);
+        assert_eq!(obj.ref_count(), 1);
+    }
+
+    #[test]
+    fn from_glib() {
+        let obj = new_object();
+        let a: PtrArray<Object> = vec![obj.clone(), obj.clone()].into_iter().collect();
+
+        let b: PtrArray<Object> = unsafe { from_glib_none(a.to_glib_none().0) };
+        assert_eq!(b.len(), 2);
+        assert_eq!(obj.ref_count(), 5);
+        assert_eq!(b.to_vec(), vec![obj.clone(), obj.clone()]);
+
+        let ptr = b.to_glib_full();
+        drop(b);
+        let c: PtrArray<Object> = unsafe { from_glib_full(ptr) };
+        assert_eq!(c.len(), 2);
+        drop(c);
+        drop(a);
+        assert_eq!(obj.ref_count(), 1);
+    }
+}
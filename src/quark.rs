@@ -3,9 +3,12 @@
 // Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
 
 use glib_sys;
+use gobject_sys;
 use std::ffi::CStr;
 use std::fmt;
 use translate::*;
+use types::{StaticType, Type};
+use value::{FromValueOptional, SetValue, SetValueOptional, Value};
 
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
 #[repr(transparent)]
@@ -56,3 +59,50 @@ impl ToGlib for Quark {
         self.0
     }
 }
+
+impl StaticType for Quark {
+    fn static_type() -> Type {
+        Type::U32
+    }
+}
+
+impl<'a> FromValueOptional<'a> for Quark {
+    unsafe fn from_value_optional(value: &'a Value) -> Option<Self> {
+        match gobject_sys::g_value_get_uint(value.to_glib_none().0) {
+            0 => None,
+            x => Some(from_glib(x)),
+        }
+    }
+}
+
+impl SetValue for Quark {
+    unsafe fn set_value(value: &mut Value, this: &Self) {
+        gobject_sys::g_value_set_uint(value.to_glib_none_mut().0, this.to_glib())
+    }
+}
+
+impl SetValueOptional for Quark {
+    unsafe fn set_value_optional(value: &mut Value, this: Option<&Self>) {
+        gobject_sys::g_value_set_uint(
+            value.to_glib_none_mut().0,
+            this.map(ToGlib::to_glib).unwrap_or(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use value::ToValue;
+
+    #[test]
+    fn test_value() {
+        let q = Quark::from_string("some-quark");
+        let v = q.to_value();
+        assert_eq!(v.type_(), Type::U32);
+        assert_eq!(v.get::<Quark>(), Ok(Some(q)));
+
+        let v = None::<Quark>.to_value();
+        assert_eq!(v.get::<Quark>(), Ok(None));
+    }
+}
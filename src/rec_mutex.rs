@@ -0,0 +1,200 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use futures_core::future::Future;
+use futures_core::task::{Context, Poll};
+use glib_sys;
+use mutex::LockRetry;
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use translate::*;
+
+/// A recursive mutual exclusion lock, i.e. a `GRecMutex`.
+///
+/// Different to [`Mutex`](struct.Mutex.html) this can be locked multiple times from the same
+/// thread and has to be unlocked as many times. It is meant for sharing a lock with C code,
+/// e.g. a `GRecMutex` that is part of a C struct via [`from_glib_ptr`].
+///
+/// [`from_glib_ptr`]: #method.from_glib_ptr
+#[repr(transparent)]
+pub struct RecMutex(UnsafeCell<glib_sys::GRecMutex>);
+
+unsafe impl Send for RecMutex {}
+unsafe impl Sync for RecMutex {}
+
+impl RecMutex {
+    /// Creates a new unlocked recursive mutex.
+    pub fn new() -> Self {
+        unsafe {
+            let mut mutex = mem::MaybeUninit::zeroed();
+            glib_sys::g_rec_mutex_init(mutex.as_mut_ptr());
+            RecMutex(UnsafeCell::new(mutex.assume_init()))
+        }
+    }
+
+    /// Borrows the mutex stored at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an initialized `GRecMutex` that stays valid for the lifetime `'a`.
+    pub unsafe fn from_glib_ptr<'a>(ptr: *mut glib_sys::GRecMutex) -> &'a Self {
+        assert!(!ptr.is_null());
+        &*(ptr as *const RecMutex)
+    }
+
+    /// Locks the mutex, blocking the current thread until it is available or returning
+    /// immediately if the current thread already holds it.
+    pub fn lock(&self) -> RecMutexGuard {
+        unsafe {
+            glib_sys::g_rec_mutex_lock(self.0.get());
+        }
+        RecMutexGuard(self, PhantomData)
+    }
+
+    /// Locks the mutex if it is currently available.
+    pub fn try_lock(&self) -> Option<RecMutexGuard> {
+        unsafe {
+            if from_glib(glib_sys::g_rec_mutex_trylock(self.0.get())) {
+                Some(RecMutexGuard(self, PhantomData))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns a future that locks the mutex without blocking the thread.
+    ///
+    /// As a `GRecMutex` can't notify waiters, locking is retried with exponential backoff on the
+    /// thread default main context of the thread the future is polled on.
+    ///
+    /// The lock is owned by the thread, not by the task that polled the future. This gives no
+    /// mutual exclusion between tasks running on the same thread: the future resolves
+    /// immediately if another task on the same thread currently holds the lock.
+    pub fn lock_future(&self) -> RecMutexLockFuture {
+        RecMutexLockFuture(LockRetry::new(self))
+    }
+}
+
+impl Default for RecMutex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for RecMutex {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_rec_mutex_clear(self.0.get());
+        }
+    }
+}
+
+impl fmt::Debug for RecMutex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RecMutex")
+    }
+}
+
+/// Guard for a locked [`RecMutex`](struct.RecMutex.html), unlocking it when dropped.
+///
+/// A `GRecMutex` must be unlocked from the thread that locked it, so this is not `Send`.
+#[must_use = "if unused the mutex will immediately be unlocked"]
+pub struct RecMutexGuard<'a>(&'a RecMutex, PhantomData<*const ()>);
+
+impl<'a> Drop for RecMutexGuard<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_rec_mutex_unlock((self.0).0.get());
+        }
+    }
+}
+
+impl<'a> fmt::Debug for RecMutexGuard<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RecMutexGuard").field(&self.0).finish()
+    }
+}
+
+/// Future returned by [`RecMutex::lock_future`](struct.RecMutex.html#method.lock_future).
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct RecMutexLockFuture<'a>(LockRetry<'a, RecMutex>);
+
+impl<'a> Future for RecMutexLockFuture<'a> {
+    type Output = RecMutexGuard<'a>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<RecMutexGuard<'a>> {
+        self.get_mut().0.poll_lock(ctx, RecMutex::try_lock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time::Duration;
+    use MainContext;
+
+    #[test]
+    fn test_lock() {
+        let mutex = Arc::new(RecMutex::new());
+
+        let guard = mutex.lock();
+        let guard2 = mutex.try_lock();
+        assert!(guard2.is_some());
+
+        let mutex_clone = mutex.clone();
+        let locked = thread::spawn(move || mutex_clone.try_lock().is_some())
+            .join()
+            .unwrap();
+        assert!(!locked);
+
+        drop(guard);
+        drop(guard2);
+
+        let mutex_clone = mutex.clone();
+        let locked = thread::spawn(move || mutex_clone.try_lock().is_some())
+            .join()
+            .unwrap();
+        assert!(locked);
+    }
+
+    #[test]
+    fn test_lock_future() {
+        let c = MainContext::new();
+        let mutex = Arc::new(RecMutex::new());
+
+        let (locked_sender, locked_receiver) = mpsc::channel();
+        let mutex_clone = mutex.clone();
+        let t = thread::spawn(move || {
+            let _guard = mutex_clone.lock();
+            locked_sender.send(()).unwrap();
+            thread::sleep(Duration::from_millis(50));
+        });
+
+        locked_receiver.recv().unwrap();
+        assert!(mutex.try_lock().is_none());
+
+        let guard = c.block_on(mutex.lock_future());
+        drop(guard);
+
+        t.join().unwrap();
+    }
+
+    #[test]
+    fn test_lock_future_same_thread() {
+        let c = MainContext::new();
+        let mutex = RecMutex::new();
+
+        // Held by this thread already, so this resolves without waiting
+        let guard = mutex.lock();
+        let guard2 = c.block_on(mutex.lock_future());
+        drop(guard2);
+        drop(guard);
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use object::ObjectType;
+use std::fmt;
+use std::ops;
+
+/// Allows sending objects of thread-safe types that don't implement `Send` and `Sync` to other
+/// threads.
+///
+/// Object wrappers only implement `Send` and `Sync` if the wrapped type is known to be
+/// thread-safe. For types where this is not the case for the wrapper, but the author of the
+/// type guarantees that all its API can be used from multiple threads at once, this can be used
+/// instead of a custom newtype.
+pub struct SendObject<T: ObjectType>(T);
+
+unsafe impl<T: ObjectType> Send for SendObject<T> {}
+unsafe impl<T: ObjectType> Sync for SendObject<T> {}
+
+impl<T: ObjectType> SendObject<T> {
+    /// Wraps `obj` to make it `Send` and `Sync`.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for ensuring that the type of `obj`, including all of its
+    /// methods, signal handlers and virtual methods that can be called through `T`, is
+    /// thread-safe. In particular this means that no thread-local state must be referenced.
+    pub unsafe fn new(obj: T) -> Self {
+        SendObject(obj)
+    }
+
+    /// Returns the wrapped object.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: ObjectType> Clone for SendObject<T> {
+    fn clone(&self) -> Self {
+        SendObject(self.0.clone())
+    }
+}
+
+impl<T: ObjectType> fmt::Debug for SendObject<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SendObject").field(&self.0).finish()
+    }
+}
+
+impl<T: ObjectType> ops::Deref for SendObject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ObjectType> AsRef<T> for SendObject<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::ObjectExt;
+    use std::thread;
+    use Object;
+    use StaticType;
+
+    #[test]
+    fn test_send_object() {
+        let obj = Object::new(Object::static_type(), &[]).unwrap();
+        let send_obj = unsafe { SendObject::new(obj.clone()) };
+
+        let send_obj_clone = send_obj.clone();
+        let type_ = thread::spawn(move || send_obj_clone.get_type())
+            .join()
+            .unwrap();
+        assert_eq!(type_, Object::static_type());
+
+        assert_eq!(send_obj.into_inner(), obj);
+    }
+}
@@ -12,65 +12,66 @@ use std::ptr;
 use translate::*;
 
 /// Wrapper implementations for shared types. See `glib_wrapper!`.
+///
+/// This can also be used directly by other crates to define wrappers for reference counted
+/// types with custom memory management or `Value` handling, e.g. for mini-object-like types
+/// that are not boxed types, by combining the following rules:
+///
+/// - `@generic_impl [$attrs] $name, $ffi_name` defines `pub struct $name` with the given
+///   attributes and implements all `translate` traits for it. `$name` has to implement
+///   [`SharedMemoryManager<$ffi_name>`](shared/trait.SharedMemoryManager.html).
+/// - `@memory_manager_impl $name, $ffi_name, @ref $arg $expr, @unref $arg $expr` implements
+///   `SharedMemoryManager` with the given expressions, which are evaluated in an `unsafe`
+///   context with `$arg: *mut $ffi_name`.
+/// - `@value_impl $name, $ffi_name, @get_type $expr` implements `StaticType` with the `GType`
+///   returned by `$expr` and stores the value as boxed type in a `Value`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate glib;
+///
+/// use glib::glib_sys;
+/// use glib::prelude::*;
+/// use glib::translate::*;
+///
+/// glib_shared_wrapper!(@generic_impl [] MyBytes, glib_sys::GBytes);
+/// glib_shared_wrapper!(@memory_manager_impl MyBytes, glib_sys::GBytes,
+///     @ref ptr glib_sys::g_bytes_ref(ptr), @unref ptr glib_sys::g_bytes_unref(ptr));
+/// glib_shared_wrapper!(@value_impl MyBytes, glib_sys::GBytes,
+///     @get_type glib_sys::g_bytes_get_type());
+///
+/// fn main() {
+///     let bytes: MyBytes =
+///         unsafe { from_glib_full(glib_sys::g_bytes_new(b"abc".as_ptr() as *const _, 3)) };
+///
+///     let value = bytes.to_value();
+///     let bytes = value.get::<MyBytes>().unwrap().unwrap();
+///     assert_eq!(unsafe { glib_sys::g_bytes_get_size(bytes.to_glib_none().0) }, 3);
+/// }
+/// ```
 #[macro_export]
 macro_rules! glib_shared_wrapper {
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @ref $ref_arg:ident $ref_expr:expr,
      @unref $unref_arg:ident $unref_expr:expr,
      @get_type $get_type_expr:expr) => {
-        glib_shared_wrapper!([$($attr)*] $name, $ffi_name, @ref $ref_arg $ref_expr,
+        $crate::glib_shared_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_shared_wrapper!(@memory_manager_impl $name, $ffi_name, @ref $ref_arg $ref_expr,
             @unref $unref_arg $unref_expr);
-
-        impl $crate::types::StaticType for $name {
-            fn static_type() -> $crate::types::Type {
-                #[allow(unused_unsafe)]
-                unsafe { $crate::translate::from_glib($get_type_expr) }
-            }
-        }
-
-        #[doc(hidden)]
-        impl<'a> $crate::value::FromValueOptional<'a> for $name {
-            #[allow(clippy::missing_safety_doc)]
-            unsafe fn from_value_optional(value: &$crate::Value) -> Option<Self> {
-                $crate::translate::from_glib_full($crate::gobject_sys::g_value_dup_boxed($crate::translate::ToGlibPtr::to_glib_none(value).0) as *mut $ffi_name)
-            }
-        }
-
-        #[doc(hidden)]
-        impl $crate::value::SetValue for $name {
-            #[allow(clippy::missing_safety_doc)]
-            unsafe fn set_value(value: &mut $crate::Value, this: &Self) {
-                $crate::gobject_sys::g_value_set_boxed($crate::translate::ToGlibPtrMut::to_glib_none_mut(value).0, $crate::translate::ToGlibPtr::<*mut $ffi_name>::to_glib_none(this).0 as $crate::glib_sys::gpointer)
-            }
-        }
-
-        #[doc(hidden)]
-        impl $crate::value::SetValueOptional for $name {
-            #[allow(clippy::missing_safety_doc)]
-            unsafe fn set_value_optional(value: &mut $crate::Value, this: Option<&Self>) {
-                $crate::gobject_sys::g_value_set_boxed($crate::translate::ToGlibPtrMut::to_glib_none_mut(value).0, $crate::translate::ToGlibPtr::<*mut $ffi_name>::to_glib_none(&this).0 as $crate::glib_sys::gpointer)
-            }
-        }
+        $crate::glib_shared_wrapper!(@value_impl $name, $ffi_name, @get_type $get_type_expr);
     };
 
     ([$($attr:meta)*] $name:ident, $ffi_name:ty, @ref $ref_arg:ident $ref_expr:expr,
      @unref $unref_arg:ident $unref_expr:expr) => {
+        $crate::glib_shared_wrapper!(@generic_impl [$($attr)*] $name, $ffi_name);
+        $crate::glib_shared_wrapper!(@memory_manager_impl $name, $ffi_name, @ref $ref_arg $ref_expr,
+            @unref $unref_arg $unref_expr);
+    };
+
+    (@generic_impl [$($attr:meta)*] $name:ident, $ffi_name:ty) => {
         $(#[$attr])*
         #[derive(Clone)]
         pub struct $name($crate::shared::Shared<$ffi_name, $name>);
 
-        #[doc(hidden)]
-        impl $crate::shared::SharedMemoryManager<$ffi_name> for $name {
-            #[inline]
-            unsafe fn ref_($ref_arg: *mut $ffi_name) {
-                $ref_expr;
-            }
-
-            #[inline]
-            unsafe fn unref($unref_arg: *mut $ffi_name) {
-                $unref_expr
-            }
-        }
-
         #[doc(hidden)]
         impl $crate::translate::GlibPtrDefault for $name {
             type GlibType = *mut $ffi_name;
@@ -279,9 +280,59 @@ macro_rules! glib_shared_wrapper {
                 unimplemented!()
             }
         }
-    }
+    };
+
+    (@memory_manager_impl $name:ident, $ffi_name:ty, @ref $ref_arg:ident $ref_expr:expr,
+     @unref $unref_arg:ident $unref_expr:expr) => {
+        #[doc(hidden)]
+        impl $crate::shared::SharedMemoryManager<$ffi_name> for $name {
+            #[inline]
+            unsafe fn ref_($ref_arg: *mut $ffi_name) {
+                $ref_expr;
+            }
+
+            #[inline]
+            unsafe fn unref($unref_arg: *mut $ffi_name) {
+                $unref_expr
+            }
+        }
+    };
+
+    (@value_impl $name:ident, $ffi_name:ty, @get_type $get_type_expr:expr) => {
+        impl $crate::types::StaticType for $name {
+            fn static_type() -> $crate::types::Type {
+                #[allow(unused_unsafe)]
+                unsafe { $crate::translate::from_glib($get_type_expr) }
+            }
+        }
+
+        #[doc(hidden)]
+        impl<'a> $crate::value::FromValueOptional<'a> for $name {
+            #[allow(clippy::missing_safety_doc)]
+            unsafe fn from_value_optional(value: &$crate::Value) -> Option<Self> {
+                $crate::translate::from_glib_full($crate::gobject_sys::g_value_dup_boxed($crate::translate::ToGlibPtr::to_glib_none(value).0) as *mut $ffi_name)
+            }
+        }
+
+        #[doc(hidden)]
+        impl $crate::value::SetValue for $name {
+            #[allow(clippy::missing_safety_doc)]
+            unsafe fn set_value(value: &mut $crate::Value, this: &Self) {
+                $crate::gobject_sys::g_value_set_boxed($crate::translate::ToGlibPtrMut::to_glib_none_mut(value).0, $crate::translate::ToGlibPtr::<*mut $ffi_name>::to_glib_none(this).0 as $crate::glib_sys::gpointer)
+            }
+        }
+
+        #[doc(hidden)]
+        impl $crate::value::SetValueOptional for $name {
+            #[allow(clippy::missing_safety_doc)]
+            unsafe fn set_value_optional(value: &mut $crate::Value, this: Option<&Self>) {
+                $crate::gobject_sys::g_value_set_boxed($crate::translate::ToGlibPtrMut::to_glib_none_mut(value).0, $crate::translate::ToGlibPtr::<*mut $ffi_name>::to_glib_none(&this).0 as $crate::glib_sys::gpointer)
+            }
+        }
+    };
 }
 
+/// Memory management functions for a shared type.
 pub trait SharedMemoryManager<T> {
     /// # Safety
     ///
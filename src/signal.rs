@@ -4,18 +4,38 @@
 
 //! `IMPL` Low level signal support.
 
-use glib_sys::{gboolean, gpointer};
+use closure::{next_closure_arg, FromClosureArg};
+use glib_sys::{self, gboolean, gpointer};
 use gobject_sys::{self, GCallback};
-use libc::{c_char, c_ulong, c_void};
+use libc::{c_char, c_uint, c_ulong, c_void};
 use object::ObjectType;
+use once_cell::sync::Lazy;
+use quark::Quark;
 use std::mem;
 use std::num::NonZeroU64;
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use subclass::SignalInvocationHint;
 use translate::{from_glib, FromGlib, ToGlib, ToGlibPtr};
+use types::StaticType;
+use value::{FromValueOptional, SetValue, SetValueOptional, ToValue};
+use BoolError;
+use Type;
+use Value;
 
 /// The id of a signal that is returned by `connect`.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct SignalHandlerId(NonZeroU64);
 
+impl SignalHandlerId {
+    /// Returns the raw handler id as used by the C API.
+    pub fn as_raw(self) -> c_ulong {
+        self.0.get() as c_ulong
+    }
+}
+
 impl ToGlib for SignalHandlerId {
     type GlibType = c_ulong;
 
@@ -58,7 +78,9 @@ pub unsafe fn connect_raw<F>(
 ) -> SignalHandlerId {
     unsafe extern "C" fn destroy_closure<F>(ptr: *mut c_void, _: *mut gobject_sys::GClosure) {
         // destroy
-        Box::<F>::from_raw(ptr as *mut _);
+        ::guard_ffi(|| {
+            Box::<F>::from_raw(ptr as *mut _);
+        })
     }
     assert_eq!(mem::size_of::<*mut F>(), mem::size_of::<gpointer>());
     assert!(trampoline.is_some());
@@ -71,10 +93,98 @@ pub unsafe fn connect_raw<F>(
         0,
     );
     assert!(handle > 0);
+    track_handler(receiver, handle);
     from_glib(handle)
 }
 
-pub fn signal_handler_block<T: ObjectType>(instance: &T, handler_id: &SignalHandlerId) {
+// Handlers connected through the bindings are tracked per instance in a `Mutex<Vec<c_ulong>>`
+// stored as qdata, so that they can be disconnected all at once. Tracking is opt-in per instance
+// via `ObjectExt::track_signal_handlers()`, and as long as no instance opted in, connecting a
+// handler doesn't even look up the qdata.
+static HANDLERS_QUARK: Lazy<Quark> = Lazy::new(|| Quark::from_string("glib-rs-signal-handlers"));
+static TRACKING_USED: AtomicBool = AtomicBool::new(false);
+
+type Handlers = Mutex<Vec<c_ulong>>;
+
+unsafe extern "C" fn free_handlers(ptr: gpointer) {
+    ::guard_ffi(|| {
+        Box::<Handlers>::from_raw(ptr as *mut _);
+    })
+}
+
+unsafe fn get_handlers<'a>(instance: *mut gobject_sys::GObject) -> Option<&'a Handlers> {
+    if !TRACKING_USED.load(Ordering::Acquire) {
+        return None;
+    }
+
+    let handlers =
+        gobject_sys::g_object_get_qdata(instance, HANDLERS_QUARK.to_glib()) as *const Handlers;
+    if handlers.is_null() {
+        None
+    } else {
+        Some(&*handlers)
+    }
+}
+
+pub(crate) unsafe fn enable_handler_tracking(instance: *mut gobject_sys::GObject) {
+    TRACKING_USED.store(true, Ordering::Release);
+
+    if get_handlers(instance).is_some() {
+        return;
+    }
+
+    // Another thread might be setting the qdata at the same time, only one of us wins. The qdata
+    // is never replaced afterwards and lives as long as the instance.
+    let handlers = Box::into_raw(Box::new(Handlers::default()));
+    let replaced: bool = from_glib(gobject_sys::g_object_replace_qdata(
+        instance,
+        HANDLERS_QUARK.to_glib(),
+        ptr::null_mut(),
+        handlers as gpointer,
+        Some(free_handlers),
+        ptr::null_mut(),
+    ));
+    if !replaced {
+        Box::from_raw(handlers);
+    }
+}
+
+pub(crate) unsafe fn track_handler(instance: *mut gobject_sys::GObject, handler: c_ulong) {
+    let mut handlers = match get_handlers(instance) {
+        Some(handlers) => handlers.lock().unwrap(),
+        None => return,
+    };
+
+    // Drop handlers that were disconnected behind our back before growing the storage
+    if handlers.len() == handlers.capacity() {
+        handlers.retain(|&id| from_glib(gobject_sys::g_signal_handler_is_connected(instance, id)));
+    }
+    handlers.push(handler);
+}
+
+pub(crate) unsafe fn untrack_handler(instance: *mut gobject_sys::GObject, handler: c_ulong) {
+    if let Some(handlers) = get_handlers(instance) {
+        handlers.lock().unwrap().retain(|&id| id != handler);
+    }
+}
+
+pub(crate) unsafe fn untrack_disconnected_handlers(instance: *mut gobject_sys::GObject) {
+    if let Some(handlers) = get_handlers(instance) {
+        handlers
+            .lock()
+            .unwrap()
+            .retain(|&id| from_glib(gobject_sys::g_signal_handler_is_connected(instance, id)));
+    }
+}
+
+pub(crate) unsafe fn take_tracked_handlers(instance: *mut gobject_sys::GObject) -> Vec<c_ulong> {
+    match get_handlers(instance) {
+        Some(handlers) => mem::take(&mut *handlers.lock().unwrap()),
+        None => Vec::new(),
+    }
+}
+
+pub fn signal_handler_block<T: ObjectType>(instance: &T, handler_id: SignalHandlerId) {
     unsafe {
         gobject_sys::g_signal_handler_block(
             instance.as_object_ref().to_glib_none().0,
@@ -83,7 +193,7 @@ pub fn signal_handler_block<T: ObjectType>(instance: &T, handler_id: &SignalHand
     }
 }
 
-pub fn signal_handler_unblock<T: ObjectType>(instance: &T, handler_id: &SignalHandlerId) {
+pub fn signal_handler_unblock<T: ObjectType>(instance: &T, handler_id: SignalHandlerId) {
     unsafe {
         gobject_sys::g_signal_handler_unblock(
             instance.as_object_ref().to_glib_none().0,
@@ -92,13 +202,16 @@ pub fn signal_handler_unblock<T: ObjectType>(instance: &T, handler_id: &SignalHa
     }
 }
 
-#[allow(clippy::needless_pass_by_value)]
 pub fn signal_handler_disconnect<T: ObjectType>(instance: &T, handler_id: SignalHandlerId) {
     unsafe {
         gobject_sys::g_signal_handler_disconnect(
             instance.as_object_ref().to_glib_none().0,
             handler_id.to_glib(),
         );
+        untrack_handler(
+            instance.as_object_ref().to_glib_none().0,
+            handler_id.to_glib(),
+        );
     }
 }
 
@@ -110,3 +223,289 @@ pub fn signal_stop_emission_by_name<T: ObjectType>(instance: &T, signal_name: &s
         );
     }
 }
+
+/// Tuples of arguments for a signal emission.
+///
+/// This is implemented for `()` and tuples of up to 10 values that implement `ToValue`, see
+/// [`ObjectImplExt::emit_typed`](../subclass/object/trait.ObjectImplExt.html#tymethod.emit_typed).
+pub trait SignalArgs {
+    /// Returns references to all arguments.
+    fn to_value_refs(&self) -> Vec<&dyn ToValue>;
+}
+
+impl SignalArgs for () {
+    fn to_value_refs(&self) -> Vec<&dyn ToValue> {
+        Vec::new()
+    }
+}
+
+macro_rules! signal_args_tuple {
+    ($($name:ident $n:tt),+) => {
+        impl<$($name: ToValue),+> SignalArgs for ($($name,)+) {
+            fn to_value_refs(&self) -> Vec<&dyn ToValue> {
+                vec![$(&self.$n as &dyn ToValue),+]
+            }
+        }
+    };
+}
+
+signal_args_tuple!(A 0);
+signal_args_tuple!(A 0, B 1);
+signal_args_tuple!(A 0, B 1, C 2);
+signal_args_tuple!(A 0, B 1, C 2, D 3);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+signal_args_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+
+/// Return value of a signal emission.
+///
+/// This is implemented for `()`, which ignores the return value, for all types that can be
+/// retrieved from a `Value`, in which case the signal must return a value of that type, and for
+/// `Option`s of them, which also allow no return value or `None`.
+pub trait SignalReturn: Sized {
+    /// Converts the return value of the signal emission.
+    fn from_return_value(value: Option<Value>) -> Result<Self, BoolError>;
+}
+
+impl SignalReturn for () {
+    fn from_return_value(_value: Option<Value>) -> Result<Self, BoolError> {
+        Ok(())
+    }
+}
+
+impl<T: for<'a> FromValueOptional<'a>> SignalReturn for T {
+    fn from_return_value(value: Option<Value>) -> Result<Self, BoolError> {
+        Option::<T>::from_return_value(value)?
+            .ok_or_else(|| glib_bool_error!("Signal returned no value"))
+    }
+}
+
+impl<T: for<'a> FromValueOptional<'a>> SignalReturn for Option<T> {
+    fn from_return_value(value: Option<Value>) -> Result<Self, BoolError> {
+        match value {
+            Some(value) => value
+                .get::<T>()
+                .map_err(|err| glib_bool_error!("Invalid signal return value: {}", err)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Tuples of arguments of a typed signal class handler.
+///
+/// This is implemented for `()` and tuples of up to 10 values that implement `StaticType` and
+/// [`FromClosureArg`], see
+/// [`ObjectClassSubclassExt::add_signal_with_typed_class_handler`](../subclass/object/trait.ObjectClassSubclassExt.html#method.add_signal_with_typed_class_handler).
+///
+/// [`FromClosureArg`]: ../closure/trait.FromClosureArg.html
+pub trait SignalHandlerArgs: Sized {
+    /// Returns the types of the arguments, not including the instance.
+    fn arg_types() -> Vec<Type>;
+
+    /// Extracts the arguments from the values of the emission, not including the instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are too few values or if they are of the wrong type.
+    fn from_values(values: &[Value]) -> Self;
+}
+
+impl SignalHandlerArgs for () {
+    fn arg_types() -> Vec<Type> {
+        Vec::new()
+    }
+
+    fn from_values(_values: &[Value]) -> Self {}
+}
+
+macro_rules! signal_handler_args_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: StaticType + for<'a> FromClosureArg<'a>),+> SignalHandlerArgs for ($($name,)+) {
+            fn arg_types() -> Vec<Type> {
+                vec![$($name::static_type()),+]
+            }
+
+            fn from_values(values: &[Value]) -> Self {
+                let mut values = values.iter();
+                ($(next_closure_arg::<$name>(&mut values),)+)
+            }
+        }
+    };
+}
+
+signal_handler_args_tuple!(A);
+signal_handler_args_tuple!(A, B);
+signal_handler_args_tuple!(A, B, C);
+signal_handler_args_tuple!(A, B, C, D);
+signal_handler_args_tuple!(A, B, C, D, E);
+signal_handler_args_tuple!(A, B, C, D, E, F);
+signal_handler_args_tuple!(A, B, C, D, E, F, G);
+signal_handler_args_tuple!(A, B, C, D, E, F, G, H);
+signal_handler_args_tuple!(A, B, C, D, E, F, G, H, I);
+signal_handler_args_tuple!(A, B, C, D, E, F, G, H, I, J);
+
+/// Return value of a typed signal class handler.
+///
+/// This is implemented for `()`, for signals without return value, for all types that implement
+/// `SetValue`, and for `Option`s of them for types that allow `None` values.
+pub trait SignalHandlerReturn {
+    /// Returns the return type of the signal.
+    fn return_type() -> Type;
+
+    /// Stores the return value of the class handler in the return value of the emission.
+    ///
+    /// `value` is already initialized to `return_type()`.
+    fn set_return_value(self, value: &mut Value);
+}
+
+impl SignalHandlerReturn for () {
+    fn return_type() -> Type {
+        Type::Unit
+    }
+
+    fn set_return_value(self, _value: &mut Value) {}
+}
+
+impl<T: SetValue> SignalHandlerReturn for T {
+    fn return_type() -> Type {
+        T::static_type()
+    }
+
+    fn set_return_value(self, value: &mut Value) {
+        unsafe { T::set_value(value, &self) }
+    }
+}
+
+impl<T: SetValueOptional> SignalHandlerReturn for Option<T> {
+    fn return_type() -> Type {
+        T::static_type()
+    }
+
+    fn set_return_value(self, value: &mut Value) {
+        unsafe { T::set_value_optional(value, self.as_ref()) }
+    }
+}
+
+/// Guard for an emission hook added with [`signal_add_emission_hook`].
+///
+/// The hook is removed again when this is dropped.
+///
+/// [`signal_add_emission_hook`]: fn.signal_add_emission_hook.html
+#[derive(Debug)]
+#[must_use = "if unused the emission hook will immediately be removed"]
+pub struct EmissionHookGuard {
+    signal_id: u32,
+    hook_id: c_ulong,
+}
+
+impl EmissionHookGuard {
+    /// Removes the emission hook.
+    pub fn remove(self) {}
+}
+
+impl Drop for EmissionHookGuard {
+    fn drop(&mut self) {
+        unsafe {
+            gobject_sys::g_signal_remove_emission_hook(self.signal_id, self.hook_id);
+        }
+    }
+}
+
+/// Adds a hook that is called for every emission of `signal_name` of `type_` on any instance.
+///
+/// `signal_name` can contain a detail, e.g. `notify::name`, in which case the hook is only called
+/// for emissions with that detail. The hook is called with the invocation hint and the arguments
+/// of the emission, the first of which is the instance the signal is emitted on.
+///
+/// Emission hooks can't be added for signals with the `NO_HOOKS` flag.
+pub fn signal_add_emission_hook<F>(
+    type_: Type,
+    signal_name: &str,
+    hook: F,
+) -> Result<EmissionHookGuard, BoolError>
+where
+    F: Fn(&SignalInvocationHint, &[Value]) + Send + Sync + 'static,
+{
+    unsafe extern "C" fn hook_trampoline<F>(
+        ihint: *mut gobject_sys::GSignalInvocationHint,
+        n_param_values: c_uint,
+        param_values: *const gobject_sys::GValue,
+        data: gpointer,
+    ) -> gboolean
+    where
+        F: Fn(&SignalInvocationHint, &[Value]) + Send + Sync + 'static,
+    {
+        ::guard_ffi(|| {
+            let hook: &F = &*(data as *const F);
+            hook(
+                &*(ihint as *const SignalInvocationHint),
+                slice::from_raw_parts(param_values as *const Value, n_param_values as usize),
+            );
+            // The hook is only removed via the guard
+            glib_sys::GTRUE
+        })
+    }
+
+    unsafe extern "C" fn destroy_hook<F>(data: gpointer) {
+        ::guard_ffi(|| {
+            Box::<F>::from_raw(data as *mut F);
+        })
+    }
+
+    unsafe {
+        // Signals are only registered once the class or interface is initialized
+        let class = if type_.is_a(&Type::BaseInterface) {
+            gobject_sys::g_type_default_interface_ref(type_.to_glib())
+        } else {
+            gobject_sys::g_type_class_ref(type_.to_glib())
+        };
+
+        let mut signal_id = 0;
+        let mut detail = 0;
+        let found: bool = from_glib(gobject_sys::g_signal_parse_name(
+            signal_name.to_glib_none().0,
+            type_.to_glib(),
+            &mut signal_id,
+            &mut detail,
+            true.to_glib(),
+        ));
+
+        if type_.is_a(&Type::BaseInterface) {
+            gobject_sys::g_type_default_interface_unref(class);
+        } else {
+            gobject_sys::g_type_class_unref(class);
+        }
+
+        if !found {
+            return Err(glib_bool_error!(
+                "Signal '{}' of type '{}' not found",
+                signal_name,
+                type_
+            ));
+        }
+
+        let mut query = mem::MaybeUninit::zeroed();
+        gobject_sys::g_signal_query(signal_id, query.as_mut_ptr());
+        let query = query.assume_init();
+        if query.signal_flags & gobject_sys::G_SIGNAL_NO_HOOKS != 0 {
+            return Err(glib_bool_error!(
+                "Signal '{}' of type '{}' does not allow emission hooks",
+                signal_name,
+                type_
+            ));
+        }
+
+        let hook_id = gobject_sys::g_signal_add_emission_hook(
+            signal_id,
+            detail,
+            Some(hook_trampoline::<F>),
+            Box::into_raw(Box::new(hook)) as gpointer,
+            Some(destroy_hook::<F>),
+        );
+
+        Ok(EmissionHookGuard { signal_id, hook_id })
+    }
+}
@@ -87,12 +87,16 @@ impl ToGlib for Continue {
 }
 
 unsafe extern "C" fn trampoline<F: FnMut() -> Continue + 'static>(func: gpointer) -> gboolean {
-    let func: &RefCell<F> = &*(func as *const RefCell<F>);
-    (&mut *func.borrow_mut())().to_glib()
+    ::guard_ffi(|| {
+        let func: &RefCell<F> = &*(func as *const RefCell<F>);
+        (&mut *func.borrow_mut())().to_glib()
+    })
 }
 
 unsafe extern "C" fn destroy_closure<F: FnMut() -> Continue + 'static>(ptr: gpointer) {
-    Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    ::guard_ffi(|| {
+        Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    })
 }
 
 fn into_raw<F: FnMut() -> Continue + 'static>(func: F) -> gpointer {
@@ -105,12 +109,16 @@ unsafe extern "C" fn trampoline_child_watch<F: FnMut(Pid, i32) + 'static>(
     status: i32,
     func: gpointer,
 ) {
-    let func: &RefCell<F> = &*(func as *const RefCell<F>);
-    (&mut *func.borrow_mut())(Pid(pid), status)
+    ::guard_ffi(|| {
+        let func: &RefCell<F> = &*(func as *const RefCell<F>);
+        (&mut *func.borrow_mut())(Pid(pid), status)
+    })
 }
 
 unsafe extern "C" fn destroy_closure_child_watch<F: FnMut(Pid, i32) + 'static>(ptr: gpointer) {
-    Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    ::guard_ffi(|| {
+        Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    })
 }
 
 fn into_raw_child_watch<F: FnMut(Pid, i32) + 'static>(func: F) -> gpointer {
@@ -124,15 +132,19 @@ unsafe extern "C" fn trampoline_unix_fd<F: FnMut(RawFd, IOCondition) -> Continue
     condition: glib_sys::GIOCondition,
     func: gpointer,
 ) -> gboolean {
-    let func: &RefCell<F> = &*(func as *const RefCell<F>);
-    (&mut *func.borrow_mut())(fd, from_glib(condition)).to_glib()
+    ::guard_ffi(|| {
+        let func: &RefCell<F> = &*(func as *const RefCell<F>);
+        (&mut *func.borrow_mut())(fd, from_glib(condition)).to_glib()
+    })
 }
 
 #[cfg(any(unix, feature = "dox"))]
 unsafe extern "C" fn destroy_closure_unix_fd<F: FnMut(RawFd, IOCondition) -> Continue + 'static>(
     ptr: gpointer,
 ) {
-    Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    ::guard_ffi(|| {
+        Box::<RefCell<F>>::from_raw(ptr as *mut _);
+    })
 }
 
 #[cfg(any(unix, feature = "dox"))]
@@ -702,12 +714,17 @@ where
 
 impl Source {
     pub fn attach(&self, context: Option<&MainContext>) -> SourceId {
-        unsafe {
+        let id = unsafe {
             from_glib(glib_sys::g_source_attach(
                 self.to_glib_none().0,
                 context.to_glib_none().0,
             ))
-        }
+        };
+
+        #[cfg(feature = "debug-source-tracking")]
+        ::main_context::track_source(self);
+
+        id
     }
 
     pub fn remove(tag: SourceId) -> Result<(), ::BoolError> {
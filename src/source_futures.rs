@@ -343,6 +343,108 @@ pub fn interval_stream_seconds_with_priority(
     }))
 }
 
+/// Exponential backoff timer for retry loops, based on GLib timeouts.
+///
+/// Every call to [`wait`](#method.wait) returns a `Future` that resolves after the current
+/// delay, and then multiplies the delay by the configured factor up to the configured maximum.
+/// Each delay is randomly jittered to between half and the full value to avoid many clients
+/// retrying in lockstep. Like all GLib timeouts a single delay is at most `u32::MAX`
+/// milliseconds, i.e. about 49 days.
+///
+/// Created with [`backoff_future`](fn.backoff_future.html).
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    priority: Priority,
+    initial: Duration,
+    max: Duration,
+    factor: f64,
+    current: Duration,
+}
+
+impl Backoff {
+    /// Returns the delay before jitter that the next call to `wait()` is based on.
+    pub fn current_delay(&self) -> Duration {
+        self.current
+    }
+
+    /// Resets the delay to the initial value, e.g. after a successful attempt.
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+
+    /// Create a `Future` that will resolve after the next backoff delay.
+    ///
+    /// The `Future` must be spawned on an `Executor` backed by a `glib::MainContext`.
+    pub fn wait(&mut self) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let delay = self.next_delay();
+        timeout_future_with_priority(self.priority, delay)
+    }
+
+    /// Create a `Stream` that will provide a value after each backoff delay.
+    ///
+    /// The `Stream` must be spawned on an `Executor` backed by a `glib::MainContext`.
+    pub fn into_stream(self) -> Pin<Box<dyn Stream<Item = ()> + Send + 'static>> {
+        Box::pin(futures_util::stream::unfold(self, |mut backoff| {
+            backoff.wait().map(move |()| Some(((), backoff)))
+        }))
+    }
+
+    fn next_delay(&mut self) -> Duration {
+        // Computed in seconds so that large factors or delays can't overflow the `Duration`
+        let current = self.current.as_secs_f64();
+        // GLib timeouts take the interval as `u32` milliseconds
+        let delay = f64::min(
+            current * ::random_double_range(0.5, 1.0),
+            f64::from(u32::max_value()) / 1000.0,
+        );
+        let delay = Duration::from_secs_f64(delay);
+
+        let next = current * self.factor;
+        self.current = if next >= self.max.as_secs_f64() {
+            self.max
+        } else {
+            Duration::from_secs_f64(next)
+        };
+
+        delay
+    }
+}
+
+/// Create a [`Backoff`](struct.Backoff.html) timer starting at `initial` and growing by `factor`
+/// after every wait, up to `max`.
+///
+/// # Panics
+///
+/// Panics if `factor` is not finite or smaller than `1.0`, or if `initial` is larger than `max`.
+pub fn backoff_future(initial: Duration, max: Duration, factor: f64) -> Backoff {
+    backoff_future_with_priority(::PRIORITY_DEFAULT, initial, max, factor)
+}
+
+/// Create a [`Backoff`](struct.Backoff.html) timer starting at `initial` and growing by `factor`
+/// after every wait, up to `max`.
+///
+/// # Panics
+///
+/// Panics if `factor` is not finite or smaller than `1.0`, or if `initial` is larger than `max`.
+pub fn backoff_future_with_priority(
+    priority: Priority,
+    initial: Duration,
+    max: Duration,
+    factor: f64,
+) -> Backoff {
+    assert!(factor.is_finite(), "Backoff factor must be finite");
+    assert!(factor >= 1.0, "Backoff factor must be at least 1.0");
+    assert!(initial <= max, "Initial backoff delay larger than maximum");
+
+    Backoff {
+        priority,
+        initial,
+        max,
+        factor,
+        current: initial,
+    }
+}
+
 #[cfg(any(unix, feature = "dox"))]
 /// Create a `Stream` that will provide a value whenever the given UNIX signal is raised
 ///
@@ -420,6 +522,51 @@ mod tests {
         assert_eq!(count, 2);
     }
 
+    #[test]
+    fn test_backoff() {
+        let mut backoff = backoff_future(Duration::from_millis(4), Duration::from_millis(20), 2.0);
+        assert_eq!(backoff.current_delay(), Duration::from_millis(4));
+
+        let c = MainContext::new();
+        c.block_on(backoff.wait());
+        assert_eq!(backoff.current_delay(), Duration::from_millis(8));
+        c.block_on(backoff.wait());
+        c.block_on(backoff.wait());
+        assert_eq!(backoff.current_delay(), Duration::from_millis(20));
+
+        backoff.reset();
+        assert_eq!(backoff.current_delay(), Duration::from_millis(4));
+
+        let count = c.block_on(
+            backoff
+                .into_stream()
+                .take(3)
+                .fold(0, |count, ()| futures_util::future::ready(count + 1)),
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_backoff_overflow() {
+        let max = Duration::from_secs(u64::max_value());
+        let mut backoff = backoff_future(Duration::from_secs(1), max, 1e300);
+
+        backoff.next_delay();
+        assert_eq!(backoff.current_delay(), max);
+        assert!(backoff.next_delay().as_millis() <= u128::from(u32::max_value()));
+        assert_eq!(backoff.current_delay(), max);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_backoff_infinite_factor() {
+        backoff_future(
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+            ::std::f64::INFINITY,
+        );
+    }
+
     #[test]
     fn test_timeout_and_channel() {
         let c = MainContext::default();
@@ -0,0 +1,209 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use glib_sys;
+use std::error;
+use std::fmt;
+use std::ptr;
+use translate::*;
+use Error;
+
+/// Exit status of a child process, e.g. as passed to the callback of `child_watch_add()`.
+///
+/// On Unix this is the raw status as returned by `waitpid()`, on Windows it is the exit code
+/// of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExitStatus(i32);
+
+impl ExitStatus {
+    /// Creates an exit status from the raw platform-specific value.
+    pub fn from_raw(status: i32) -> Self {
+        ExitStatus(status)
+    }
+
+    /// Returns the raw platform-specific value.
+    pub fn into_raw(self) -> i32 {
+        self.0
+    }
+
+    /// Returns `true` if the process exited normally with exit code 0.
+    pub fn success(self) -> bool {
+        self.code() == Some(0)
+    }
+
+    /// Returns the exit code of the process if it exited normally.
+    #[cfg(unix)]
+    pub fn code(self) -> Option<i32> {
+        // WIFEXITED() and WEXITSTATUS()
+        if self.0 & 0x7f == 0 {
+            Some((self.0 >> 8) & 0xff)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the exit code of the process if it exited normally.
+    #[cfg(not(unix))]
+    pub fn code(self) -> Option<i32> {
+        Some(self.0)
+    }
+
+    /// Returns the signal that terminated the process, if any.
+    ///
+    /// This always returns `None` on platforms other than Unix.
+    #[cfg(unix)]
+    pub fn signal(self) -> Option<i32> {
+        // WIFSIGNALED() and WTERMSIG()
+        let signal = self.0 & 0x7f;
+        if signal != 0 && signal != 0x7f {
+            Some(signal)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the signal that terminated the process, if any.
+    ///
+    /// This always returns `None` on platforms other than Unix.
+    #[cfg(not(unix))]
+    pub fn signal(self) -> Option<i32> {
+        None
+    }
+
+    /// Returns an error if the process did not exit successfully.
+    ///
+    /// See [`spawn_check_exit_status`](fn.spawn_check_exit_status.html).
+    pub fn check(self) -> Result<(), SpawnError> {
+        spawn_check_exit_status(self)
+    }
+}
+
+impl From<i32> for ExitStatus {
+    fn from(status: i32) -> Self {
+        ExitStatus(status)
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(code) = self.code() {
+            write!(f, "exit code: {}", code)
+        } else if let Some(signal) = self.signal() {
+            write!(f, "signal: {}", signal)
+        } else {
+            write!(f, "unrecognized status: {}", self.0)
+        }
+    }
+}
+
+/// Error returned if a child process did not exit successfully.
+///
+/// This keeps the exit status together with the `GError` that describes it. The error is in
+/// the `G_SPAWN_EXIT_ERROR` domain with the exit code as error code if the process exited
+/// normally, and in the `G_SPAWN_ERROR` domain otherwise.
+#[derive(Debug)]
+pub struct SpawnError {
+    exit_status: ExitStatus,
+    error: Error,
+}
+
+impl SpawnError {
+    /// Returns the exit status of the process.
+    pub fn exit_status(&self) -> ExitStatus {
+        self.exit_status
+    }
+
+    /// Returns `true` if the process exited normally but with a non-zero exit code.
+    pub fn is_exit_error(&self) -> bool {
+        unsafe {
+            let error: *const glib_sys::GError = self.error.to_glib_none().0;
+            (*error).domain == glib_sys::g_spawn_exit_error_quark()
+        }
+    }
+
+    /// Returns the underlying error.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Converts into the underlying error.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl error::Error for SpawnError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<SpawnError> for Error {
+    fn from(err: SpawnError) -> Self {
+        err.error
+    }
+}
+
+/// Returns an error if the child process with `exit_status` did not exit successfully.
+pub fn spawn_check_exit_status(exit_status: ExitStatus) -> Result<(), SpawnError> {
+    unsafe {
+        let mut error = ptr::null_mut();
+        let _ = glib_sys::g_spawn_check_exit_status(exit_status.0, &mut error);
+        if error.is_null() {
+            Ok(())
+        } else {
+            Err(SpawnError {
+                exit_status,
+                error: from_glib_full(error),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success() {
+        let status = ExitStatus::from_raw(0);
+        assert!(status.success());
+        assert_eq!(status.code(), Some(0));
+        assert_eq!(status.signal(), None);
+        assert!(status.check().is_ok());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_exit_code() {
+        let status = ExitStatus::from_raw(3 << 8);
+        assert!(!status.success());
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(status.signal(), None);
+        assert_eq!(status.to_string(), "exit code: 3");
+
+        let err = status.check().unwrap_err();
+        assert_eq!(err.exit_status(), status);
+        assert!(err.is_exit_error());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_signal() {
+        let status = ExitStatus::from_raw(9);
+        assert!(!status.success());
+        assert_eq!(status.code(), None);
+        assert_eq!(status.signal(), Some(9));
+        assert_eq!(status.to_string(), "signal: 9");
+
+        let err = status.check().unwrap_err();
+        assert!(!err.is_exit_error());
+    }
+}
@@ -42,14 +42,18 @@ pub trait BoxedType: Clone + Sized + 'static {
 /// [`GBoxed!`]: ../../derive.GBoxed.html
 pub fn register_boxed_type<T: BoxedType>() -> ::Type {
     unsafe extern "C" fn boxed_copy<T: BoxedType>(v: glib_sys::gpointer) -> glib_sys::gpointer {
-        let v = &*(v as *mut T);
-        let copy = Box::new(v.clone());
+        ::guard_ffi(|| {
+            let v = &*(v as *mut T);
+            let copy = Box::new(v.clone());
 
-        Box::into_raw(copy) as glib_sys::gpointer
+            Box::into_raw(copy) as glib_sys::gpointer
+        })
     }
     unsafe extern "C" fn boxed_free<T: BoxedType>(v: glib_sys::gpointer) {
-        let v = v as *mut T;
-        let _ = Box::from_raw(v);
+        ::guard_ffi(|| {
+            let v = v as *mut T;
+            let _ = Box::from_raw(v);
+        })
     }
     unsafe {
         use std::ffi::CString;
@@ -252,8 +252,10 @@ unsafe extern "C" fn interface_init<T: ObjectInterface>(
     klass: glib_sys::gpointer,
     _klass_data: glib_sys::gpointer,
 ) {
-    let iface = &mut *(klass as *mut T);
-    iface.interface_init();
+    ::guard_ffi(|| {
+        let iface = &mut *(klass as *mut T);
+        iface.interface_init();
+    })
 }
 
 /// Register a `glib::Type` ID for `T`.
@@ -268,11 +268,14 @@ pub mod object;
 #[macro_use]
 pub mod boxed;
 
+pub mod type_module;
+
 pub mod prelude {
     //! Prelude that re-exports all important traits from this crate.
     pub use super::boxed::BoxedType;
     pub use super::interface::{ObjectInterface, ObjectInterfaceExt};
-    pub use super::object::{ObjectClassSubclassExt, ObjectImpl, ObjectImplExt};
+    pub use super::object::{ObjectClassSubclassExt, ObjectImpl, ObjectImplExt, ObjectNotifyImpl};
+    pub use super::type_module::{TypeModuleImpl, TypeModuleImplExt};
     pub use super::types::{
         ClassStruct, InstanceStruct, IsImplementable, IsSubclassable, ObjectSubclass,
     };
@@ -282,5 +285,6 @@ pub use self::boxed::register_boxed_type;
 pub use self::interface::register_interface;
 pub use self::object::Property;
 pub use self::types::{
-    register_type, InitializingType, SignalClassHandlerToken, SignalInvocationHint, TypeData,
+    register_dynamic_type, register_type, InitializingType, SignalClassHandlerToken,
+    SignalInvocationHint, TypeData,
 };
@@ -8,12 +8,13 @@
 use super::prelude::*;
 use glib_sys;
 use gobject_sys;
+use signal::{SignalArgs, SignalHandlerArgs, SignalHandlerReturn, SignalReturn};
 use std::borrow::Borrow;
 use std::fmt;
 use std::mem;
 use std::ptr;
 use translate::*;
-use {Object, ObjectClass, ObjectType, SignalFlags, Type, Value};
+use {BoolError, Object, ObjectClass, ObjectExt, ObjectType, SignalFlags, Type, Value};
 
 /// Trait for implementors of `glib::Object` subclasses.
 ///
@@ -45,31 +46,79 @@ pub trait ObjectImpl: ObjectSubclass + ObjectImplExt {
     }
 }
 
+/// Trait for `glib::Object` subclasses that override the property change notification virtual
+/// methods.
+///
+/// Overriding these routes every property change notification of the instance through Rust, so
+/// they are only installed if [`ObjectClassSubclassExt::override_notify`] is called from
+/// `class_init()`:
+///
+/// ```ignore
+/// impl ObjectNotifyImpl for MyObject {
+///     fn notify(&self, obj: &glib::Object, pspec: &glib::ParamSpec) {
+///         println!("{} changed", pspec.get_name());
+///         self.parent_notify(obj, pspec);
+///     }
+/// }
+///
+/// impl ObjectSubclass for MyObject {
+///     // ...
+///     fn class_init(klass: &mut Self::Class) {
+///         klass.override_notify::<Self>();
+///     }
+/// }
+/// ```
+///
+/// [`ObjectClassSubclassExt::override_notify`]: trait.ObjectClassSubclassExt.html#method.override_notify
+pub trait ObjectNotifyImpl: ObjectImpl {
+    /// Property change notification.
+    ///
+    /// This is the class handler of the `notify` signal and called whenever a property of the
+    /// instance was notified to be changed, e.g. from the subclass' own property setter.
+    ///
+    /// Should chain up to the parent class' implementation.
+    fn notify(&self, obj: &Object, pspec: &::ParamSpec) {
+        self.parent_notify(obj, pspec);
+    }
+
+    /// Dispatches property change notifications.
+    ///
+    /// This is called with all properties that were notified to be changed since the last
+    /// dispatch. The default implementation emits the `notify` signal for each of them.
+    ///
+    /// Should chain up to the parent class' implementation.
+    fn dispatch_properties_changed(&self, obj: &Object, pspecs: &[::ParamSpec]) {
+        self.parent_dispatch_properties_changed(obj, pspecs);
+    }
+}
+
 unsafe extern "C" fn get_property<T: ObjectImpl>(
     obj: *mut gobject_sys::GObject,
     id: u32,
     value: *mut gobject_sys::GValue,
     _pspec: *mut gobject_sys::GParamSpec,
 ) {
-    let instance = &*(obj as *mut T::Instance);
-    let imp = instance.get_impl();
-
-    match imp.get_property(&from_glib_borrow(obj), (id - 1) as usize) {
-        Ok(v) => {
-            // We first unset the value we get passed in, in case it contained
-            // any previous data. Then we directly overwrite it with our new
-            // value, and pass ownership of the contained data to the C GValue
-            // by forgetting it on the Rust side.
-            //
-            // Without this, by using the GValue API, we would have to create
-            // a copy of the value when setting it on the destination just to
-            // immediately free the original value afterwards.
-            gobject_sys::g_value_unset(value);
-            let v = mem::ManuallyDrop::new(v);
-            ptr::write(value, ptr::read(v.to_glib_none().0));
-        }
-        Err(()) => eprintln!("Failed to get property"),
-    }
+    ::guard_ffi(|| {
+        let instance = &*(obj as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        match imp.get_property(&from_glib_borrow(obj), (id - 1) as usize) {
+            Ok(v) => {
+                // We first unset the value we get passed in, in case it contained
+                // any previous data. Then we directly overwrite it with our new
+                // value, and pass ownership of the contained data to the C GValue
+                // by forgetting it on the Rust side.
+                //
+                // Without this, by using the GValue API, we would have to create
+                // a copy of the value when setting it on the destination just to
+                // immediately free the original value afterwards.
+                gobject_sys::g_value_unset(value);
+                let v = mem::ManuallyDrop::new(v);
+                ptr::write(value, ptr::read(v.to_glib_none().0));
+            }
+            Err(()) => eprintln!("Failed to get property"),
+        }
+    })
 }
 
 unsafe extern "C" fn set_property<T: ObjectImpl>(
@@ -78,20 +127,54 @@ unsafe extern "C" fn set_property<T: ObjectImpl>(
     value: *mut gobject_sys::GValue,
     _pspec: *mut gobject_sys::GParamSpec,
 ) {
-    let instance = &*(obj as *mut T::Instance);
-    let imp = instance.get_impl();
-    imp.set_property(
-        &from_glib_borrow(obj),
-        (id - 1) as usize,
-        &*(value as *mut Value),
-    );
+    ::guard_ffi(|| {
+        let instance = &*(obj as *mut T::Instance);
+        let imp = instance.get_impl();
+        imp.set_property(
+            &from_glib_borrow(obj),
+            (id - 1) as usize,
+            &*(value as *mut Value),
+        );
+    })
 }
 
 unsafe extern "C" fn constructed<T: ObjectImpl>(obj: *mut gobject_sys::GObject) {
-    let instance = &*(obj as *mut T::Instance);
-    let imp = instance.get_impl();
+    ::guard_ffi(|| {
+        #[cfg(feature = "debug-object-tracking")]
+        ::debug::track_object(obj);
 
-    imp.constructed(&from_glib_borrow(obj));
+        let instance = &*(obj as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        imp.constructed(&from_glib_borrow(obj));
+    })
+}
+
+unsafe extern "C" fn notify<T: ObjectNotifyImpl>(
+    obj: *mut gobject_sys::GObject,
+    pspec: *mut gobject_sys::GParamSpec,
+) {
+    ::guard_ffi(|| {
+        let instance = &*(obj as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        imp.notify(&from_glib_borrow(obj), &from_glib_borrow(pspec));
+    })
+}
+
+unsafe extern "C" fn dispatch_properties_changed<T: ObjectNotifyImpl>(
+    obj: *mut gobject_sys::GObject,
+    n_pspecs: u32,
+    pspecs: *mut *mut gobject_sys::GParamSpec,
+) {
+    ::guard_ffi(|| {
+        let instance = &*(obj as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        let pspecs: Vec<::ParamSpec> =
+            FromGlibContainer::from_glib_none_num(pspecs, n_pspecs as usize);
+        imp.dispatch_properties_changed(&from_glib_borrow(obj), &pspecs);
+    })
 }
 
 /// Definition of a property.
@@ -112,6 +195,12 @@ pub unsafe trait ObjectClassSubclassExt: Sized + 'static {
     ///
     /// The index in the properties array is going to be the index passed to the
     /// property setters and getters.
+    ///
+    /// Inherited or interface properties can be overridden by returning a `ParamSpec` created with
+    /// [`ParamSpec::override_for_class`] or [`ParamSpec::override_for_interface`].
+    ///
+    /// [`ParamSpec::override_for_class`]: ../../struct.ParamSpec.html#method.override_for_class
+    /// [`ParamSpec::override_for_interface`]: ../../struct.ParamSpec.html#method.override_for_interface
     fn install_properties<'a, T: Borrow<Property<'a>>>(&mut self, properties: &[T]) {
         if properties.is_empty() {
             return;
@@ -142,6 +231,29 @@ pub unsafe trait ObjectClassSubclassExt: Sized + 'static {
         }
     }
 
+    /// Installs the `notify` and `dispatch_properties_changed` virtual methods of `T`.
+    ///
+    /// This has to be called from `T`'s own `class_init()`, otherwise [`ObjectNotifyImpl`] is not
+    /// used.
+    ///
+    /// [`ObjectNotifyImpl`]: trait.ObjectNotifyImpl.html
+    fn override_notify<T: ObjectNotifyImpl>(&mut self) {
+        unsafe {
+            let klass = &mut *(self as *mut Self as *mut gobject_sys::GObjectClass);
+
+            // The trampolines cast the instance to `T::Instance`
+            let type_: Type = from_glib(klass.g_type_class.g_type);
+            assert_eq!(
+                type_,
+                T::type_data().as_ref().get_type(),
+                "Notify vfuncs of a different type"
+            );
+
+            klass.notify = Some(notify::<T>);
+            klass.dispatch_properties_changed = Some(dispatch_properties_changed::<T>);
+        }
+    }
+
     /// Add a new signal to the subclass.
     ///
     /// This can be emitted later by `glib::Object::emit` and external code
@@ -164,6 +276,12 @@ pub unsafe trait ObjectClassSubclassExt: Sized + 'static {
     /// can connect to the signal to get notified about emissions.
     ///
     /// The class handler will be called during the signal emission at the corresponding stage.
+    ///
+    /// The `&[Value]` passed to the class handler directly borrows the array of `GValue`s the
+    /// signal was emitted with, without copying, and the first element is the instance.
+    /// Arguments can be retrieved with [`Value::get`] on each element.
+    ///
+    /// [`Value::get`]: ../../value/struct.Value.html#method.get
     fn add_signal_with_class_handler<F>(
         &mut self,
         name: &str,
@@ -186,6 +304,77 @@ pub unsafe trait ObjectClassSubclassExt: Sized + 'static {
         }
     }
 
+    /// Add a new signal with a typed class handler to the subclass.
+    ///
+    /// Different to [`add_signal_with_class_handler`], the argument and return types of the
+    /// signal are taken from the signature of the class handler, and the arguments are passed to
+    /// it as a tuple of Rust values instead of a `&[Value]` slice.
+    ///
+    /// The class handler is invoked through a marshaller that is generated for this signature.
+    /// It extracts the arguments directly from the values of the emission and stores the return
+    /// value directly in the return value of the emission, without the intermediate `Value`s
+    /// of the generic closure marshaller.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate glib;
+    /// # use glib::prelude::*;
+    /// # use glib::subclass;
+    /// # use glib::subclass::prelude::*;
+    /// #
+    /// # struct Adder;
+    /// #
+    /// # impl ObjectSubclass for Adder {
+    /// #     const NAME: &'static str = "DocAdder";
+    /// #     type ParentType = glib::Object;
+    /// #     type Instance = subclass::simple::InstanceStruct<Self>;
+    /// #     type Class = subclass::simple::ClassStruct<Self>;
+    /// #
+    /// #     glib_object_subclass!();
+    /// #
+    /// #     fn new() -> Self {
+    /// #         Adder
+    /// #     }
+    /// #
+    /// fn class_init(klass: &mut Self::Class) {
+    ///     klass.add_signal_with_typed_class_handler(
+    ///         "add",
+    ///         glib::SignalFlags::RUN_LAST | glib::SignalFlags::ACTION,
+    ///         |_, (a, b): (i32, i32)| a + b,
+    ///     );
+    /// }
+    /// # }
+    /// #
+    /// # impl ObjectImpl for Adder {}
+    /// #
+    /// # fn main() {
+    /// let obj = glib::Object::new(Adder::get_type(), &[]).unwrap();
+    /// let sum = obj.emit("add", &[&1i32, &2i32]).unwrap();
+    /// assert_eq!(sum.unwrap().get_some::<i32>(), Ok(3));
+    /// # }
+    /// ```
+    ///
+    /// [`add_signal_with_class_handler`]: #method.add_signal_with_class_handler
+    fn add_signal_with_typed_class_handler<Args, Ret, F>(
+        &mut self,
+        name: &str,
+        flags: SignalFlags,
+        class_handler: F,
+    ) where
+        Args: SignalHandlerArgs,
+        Ret: SignalHandlerReturn,
+        F: Fn(&super::SignalClassHandlerToken, Args) -> Ret + Send + Sync + 'static,
+    {
+        unsafe {
+            super::types::add_signal_with_typed_class_handler(
+                *(self as *mut _ as *mut glib_sys::GType),
+                name,
+                flags,
+                class_handler,
+            );
+        }
+    }
+
     /// Add a new signal with accumulator to the subclass.
     ///
     /// This can be emitted later by `glib::Object::emit` and external code
@@ -284,11 +473,29 @@ pub trait ObjectImplExt {
     /// Chain up to the parent class' implementation of `glib::Object::constructed()`.
     fn parent_constructed(&self, obj: &Object);
 
+    /// Chain up to the parent class' implementation of `glib::Object::notify()`.
+    fn parent_notify(&self, obj: &Object, pspec: &::ParamSpec);
+
+    /// Chain up to the parent class' implementation of
+    /// `glib::Object::dispatch_properties_changed()`.
+    fn parent_dispatch_properties_changed(&self, obj: &Object, pspecs: &[::ParamSpec]);
+
     fn signal_chain_from_overridden(
         &self,
         token: &super::SignalClassHandlerToken,
         values: &[Value],
     ) -> Option<Value>;
+
+    /// Emits the signal `signal_name` on the instance with typed arguments and return value.
+    ///
+    /// This takes care of getting the instance and of converting the arguments and the return
+    /// value, e.g. `let s = self.emit_typed::<_, String>("create-string", (1u32, "foo"))?;`.
+    /// Use `()` as return type for signals without return value.
+    fn emit_typed<Args: SignalArgs, Ret: SignalReturn>(
+        &self,
+        signal_name: &str,
+        args: Args,
+    ) -> Result<Ret, BoolError>;
 }
 
 impl<T: ObjectImpl> ObjectImplExt for T {
@@ -303,6 +510,36 @@ impl<T: ObjectImpl> ObjectImplExt for T {
         }
     }
 
+    fn parent_notify(&self, obj: &Object, pspec: &::ParamSpec) {
+        unsafe {
+            let data = T::type_data();
+            let parent_class = data.as_ref().get_parent_class() as *mut gobject_sys::GObjectClass;
+
+            if let Some(ref func) = (*parent_class).notify {
+                func(obj.to_glib_none().0, pspec.to_glib_none().0);
+            }
+        }
+    }
+
+    fn parent_dispatch_properties_changed(&self, obj: &Object, pspecs: &[::ParamSpec]) {
+        unsafe {
+            let data = T::type_data();
+            let parent_class = data.as_ref().get_parent_class() as *mut gobject_sys::GObjectClass;
+
+            if let Some(ref func) = (*parent_class).dispatch_properties_changed {
+                let mut pspecs_ptrs = pspecs
+                    .iter()
+                    .map(|pspec| pspec.to_glib_none().0)
+                    .collect::<Vec<*mut gobject_sys::GParamSpec>>();
+                func(
+                    obj.to_glib_none().0,
+                    pspecs_ptrs.len() as u32,
+                    pspecs_ptrs.as_mut_ptr(),
+                );
+            }
+        }
+    }
+
     fn signal_chain_from_overridden(
         &self,
         token: &super::SignalClassHandlerToken,
@@ -316,6 +553,17 @@ impl<T: ObjectImpl> ObjectImplExt for T {
             )
         }
     }
+
+    fn emit_typed<Args: SignalArgs, Ret: SignalReturn>(
+        &self,
+        signal_name: &str,
+        args: Args,
+    ) -> Result<Ret, BoolError> {
+        let ret = self
+            .get_instance()
+            .emit(signal_name, &args.to_value_refs())?;
+        Ret::from_return_value(ret)
+    }
 }
 
 #[cfg(test)]
@@ -345,6 +593,33 @@ mod test {
 
     impl ObjectImpl for ChildObject {}
 
+    // An `Object` subclass that only implements `with_class()` to check that the class of the
+    // instantiated type is passed to it
+    pub struct ClassObject {
+        klass: *const subclass::simple::ClassStruct<ClassObject>,
+    }
+
+    impl ObjectSubclass for ClassObject {
+        const NAME: &'static str = "ClassObject";
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn with_class(klass: &Self::Class) -> Self {
+            ClassObject { klass }
+        }
+    }
+
+    impl ObjectImpl for ClassObject {}
+
+    glib_c_api_export!(
+        ChildObject,
+        get_type => glib_test_child_object_get_type,
+        new => glib_test_child_object_new
+    );
+
     impl StaticType for ChildObject {
         fn static_type() -> Type {
             ChildObject::get_type()
@@ -394,6 +669,8 @@ mod test {
         name: RefCell<Option<String>>,
         construct_name: RefCell<Option<String>>,
         constructed: RefCell<bool>,
+        notified: RefCell<Vec<String>>,
+        dispatched: RefCell<usize>,
     }
 
     impl ObjectSubclass for SimpleObject {
@@ -410,6 +687,7 @@ mod test {
 
         fn class_init(klass: &mut subclass::simple::ClassStruct<Self>) {
             klass.install_properties(&PROPERTIES);
+            klass.override_notify::<Self>();
 
             klass.add_signal(
                 "name-changed",
@@ -457,6 +735,12 @@ mod test {
                 &[],
                 ChildObject::static_type(),
             );
+
+            klass.add_signal_with_typed_class_handler(
+                "concat",
+                SignalFlags::RUN_LAST,
+                |_, (s, n): (String, u32)| s.repeat(n as usize),
+            );
         }
 
         fn new() -> Self {
@@ -464,6 +748,8 @@ mod test {
                 name: RefCell::new(None),
                 construct_name: RefCell::new(None),
                 constructed: RefCell::new(false),
+                notified: RefCell::new(Vec::new()),
+                dispatched: RefCell::new(0),
             }
         }
     }
@@ -515,6 +801,20 @@ mod test {
         }
     }
 
+    impl ObjectNotifyImpl for SimpleObject {
+        fn notify(&self, obj: &Object, pspec: &::ParamSpec) {
+            self.notified
+                .borrow_mut()
+                .push(pspec.get_name().to_string());
+            self.parent_notify(obj, pspec);
+        }
+
+        fn dispatch_properties_changed(&self, obj: &Object, pspecs: &[::ParamSpec]) {
+            *self.dispatched.borrow_mut() += 1;
+            self.parent_dispatch_properties_changed(obj, pspecs);
+        }
+    }
+
     #[repr(C)]
     pub struct DummyInterface {
         parent: gobject_sys::GTypeInterface,
@@ -548,6 +848,135 @@ mod test {
         }
     }
 
+    // An interface with a property that implementors have to override
+    #[repr(C)]
+    pub struct PropertyInterface {
+        parent: gobject_sys::GTypeInterface,
+    }
+
+    impl ObjectInterface for PropertyInterface {
+        const NAME: &'static str = "PropertyInterface";
+
+        glib_object_interface!();
+
+        fn type_init(type_: &mut subclass::InitializingType<Self>) {
+            type_.add_prerequisite::<Object>();
+        }
+
+        fn interface_init(&mut self) {
+            self.install_properties(&[Property("value", |name| {
+                ::ParamSpec::string(name, "Value", "Value", None, ::ParamFlags::READWRITE)
+            })]);
+        }
+    }
+
+    impl StaticType for PropertyInterface {
+        fn static_type() -> Type {
+            PropertyInterface::get_type()
+        }
+    }
+
+    unsafe impl<T: ObjectImpl> IsImplementable<T> for PropertyInterface {
+        unsafe extern "C" fn interface_init(
+            _iface: glib_sys::gpointer,
+            _iface_data: glib_sys::gpointer,
+        ) {
+        }
+    }
+
+    static OVERRIDE_PROPERTIES: [Property; 2] = [
+        Property("value", |name| {
+            ::ParamSpec::override_for_interface(name, PropertyInterface::static_type())
+        }),
+        Property("other", |name| {
+            ::ParamSpec::boolean(name, "Other", "Other", false, ::ParamFlags::READABLE)
+        }),
+    ];
+
+    pub struct OverrideObject {
+        value: RefCell<Option<String>>,
+    }
+
+    impl ObjectSubclass for OverrideObject {
+        const NAME: &'static str = "OverrideObject";
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn type_init(type_: &mut subclass::InitializingType<Self>) {
+            type_.add_interface::<PropertyInterface>();
+        }
+
+        fn class_init(klass: &mut subclass::simple::ClassStruct<Self>) {
+            klass.install_properties(&OVERRIDE_PROPERTIES);
+        }
+
+        fn new() -> Self {
+            Self {
+                value: RefCell::new(None),
+            }
+        }
+    }
+
+    impl ObjectImpl for OverrideObject {
+        fn set_property(&self, _obj: &Object, id: usize, value: &Value) {
+            match OVERRIDE_PROPERTIES[id] {
+                Property("value", ..) => {
+                    let v = value
+                        .get()
+                        .expect("type conformity checked by 'Object::set_property'");
+                    self.value.replace(v);
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        fn get_property(&self, _obj: &Object, id: usize) -> Result<Value, ()> {
+            match OVERRIDE_PROPERTIES[id] {
+                Property("value", ..) => Ok(self.value.borrow().to_value()),
+                Property("other", ..) => Ok(true.to_value()),
+                _ => unimplemented!(),
+            }
+        }
+    }
+
+    // Two types with the same name that are allowed to conflict
+    pub struct ConflictingObject;
+    impl ObjectSubclass for ConflictingObject {
+        const NAME: &'static str = "ConflictingObject";
+        const ALLOW_NAME_CONFLICT: bool = true;
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            ConflictingObject
+        }
+    }
+
+    impl ObjectImpl for ConflictingObject {}
+
+    pub struct OtherConflictingObject;
+    impl ObjectSubclass for OtherConflictingObject {
+        const NAME: &'static str = "ConflictingObject";
+        const ALLOW_NAME_CONFLICT: bool = true;
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            OtherConflictingObject
+        }
+    }
+
+    impl ObjectImpl for OtherConflictingObject {}
+
     #[test]
     fn test_create() {
         let type_ = SimpleObject::get_type();
@@ -568,6 +997,20 @@ mod test {
         assert!(weak.upgrade().is_none());
     }
 
+    #[test]
+    fn test_name_conflict() {
+        let type_ = ConflictingObject::get_type();
+        let other_type = OtherConflictingObject::get_type();
+        assert_ne!(type_, other_type);
+
+        let mut names = vec![type_.name(), other_type.name()];
+        names.sort();
+        assert_eq!(names, vec!["ConflictingObject", "ConflictingObject-1"]);
+
+        let obj = Object::new(other_type, &[]).expect("Object::new failed");
+        assert_eq!(obj.get_type(), other_type);
+    }
+
     #[test]
     fn test_create_child_object() {
         let type_ = ChildObject::get_type();
@@ -580,6 +1023,225 @@ mod test {
         assert_eq!(obj, imp.get_instance());
     }
 
+    #[test]
+    fn test_is() {
+        let obj = Object::new(ChildObject::get_type(), &[]).expect("Object::new failed");
+        assert!(obj.is::<Object>());
+        assert!(obj.is::<ChildObject>());
+        assert!(!obj.is::<DummyInterface>());
+
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        assert!(obj.is::<Object>());
+        assert!(obj.is::<DummyInterface>());
+        assert!(!obj.is::<ChildObject>());
+    }
+
+    #[test]
+    fn test_ptr_eq_and_object_id() {
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let other = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+
+        assert!(obj.ptr_eq(&obj.clone()));
+        assert!(!obj.ptr_eq(&other));
+
+        assert_eq!(obj.object_id(), obj.clone().object_id());
+        assert_ne!(obj.object_id(), other.object_id());
+    }
+
+    #[test]
+    fn test_weak_ref_eq_hash() {
+        use std::collections::HashSet;
+
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let other = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+
+        let weak = obj.downgrade();
+        assert!(weak == obj.downgrade());
+        assert!(weak != other.downgrade());
+
+        let mut set = HashSet::new();
+        set.insert(obj.downgrade());
+        set.insert(obj.downgrade());
+        set.insert(other.downgrade());
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(weak.upgrade_map(|o| o.ptr_eq(&obj)), Some(true));
+        drop(obj);
+        assert_eq!(weak.upgrade_map(|_| ()), None);
+
+        // Weak references stay equal and findable after the object is gone
+        assert!(weak == weak.clone());
+        assert!(weak != ::WeakRef::<Object>::new());
+        assert!(set.remove(&weak));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_notify() {
+        use std::sync::{Arc, Mutex};
+
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let imp = SimpleObject::from_instance(&obj);
+        imp.notified.borrow_mut().clear();
+        *imp.dispatched.borrow_mut() = 0;
+
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let notified_clone = notified.clone();
+        obj.connect_notify(None, move |_, pspec| {
+            notified_clone
+                .lock()
+                .unwrap()
+                .push(pspec.get_name().to_string());
+        });
+
+        obj.set_property("name", &"foo")
+            .expect("Failed to set property");
+        obj.notify("construct-name");
+
+        assert_eq!(*imp.notified.borrow(), vec!["name", "construct-name"]);
+        assert_eq!(*imp.dispatched.borrow(), 2);
+        assert_eq!(*notified.lock().unwrap(), vec!["name", "construct-name"]);
+
+        // Without override_notify() the vfunc is not installed. GObject itself has none.
+        unsafe {
+            let klass = gobject_sys::g_type_class_ref(ChildObject::get_type().to_glib())
+                as *const gobject_sys::GObjectClass;
+            assert!((*klass).notify.is_none());
+            gobject_sys::g_type_class_unref(klass as glib_sys::gpointer);
+        }
+    }
+
+    #[test]
+    fn test_interfaces() {
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        assert_eq!(obj.interfaces(), vec![DummyInterface::static_type()]);
+        assert_eq!(
+            obj.get_object_class().interfaces(),
+            vec![DummyInterface::static_type()]
+        );
+        assert!(obj.implements::<DummyInterface>());
+        assert!(!obj.implements::<Object>());
+
+        let obj = Object::new(ChildObject::get_type(), &[]).expect("Object::new failed");
+        assert!(obj.interfaces().is_empty());
+        assert!(!obj.implements::<DummyInterface>());
+    }
+
+    #[test]
+    fn test_option_and_iterator_casts() {
+        let source = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let target = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let binding = source
+            .bind_property("name", &target, "name")
+            .build()
+            .expect("Failed to create binding");
+
+        let obj = Some(binding.clone().upcast::<Object>());
+        assert_eq!(
+            obj.clone().and_downcast::<::Binding>(),
+            Some(binding.clone())
+        );
+        assert_eq!(obj.and_downcast_ref::<::Binding>(), Some(&binding));
+        assert_eq!(Some(source.clone()).and_downcast::<::Binding>(), None);
+        assert_eq!(None::<Object>.and_downcast::<::Binding>(), None);
+        assert_eq!(
+            Some(binding.clone()).and_upcast::<Object>(),
+            Some(binding.clone().upcast())
+        );
+        assert_eq!(
+            Some(binding.clone()).and_upcast_ref::<Object>(),
+            Some(binding.upcast_ref())
+        );
+        assert_eq!(
+            obj.clone().and_dynamic_cast::<::Binding>(),
+            Some(binding.clone())
+        );
+        assert_eq!(obj.and_dynamic_cast_ref::<::Binding>(), Some(&binding));
+
+        let objects = vec![source.clone(), binding.clone().upcast(), target.clone()];
+        assert_eq!(
+            objects
+                .iter()
+                .cloned()
+                .filter_downcast::<::Binding>()
+                .collect::<Vec<_>>(),
+            vec![binding.clone()]
+        );
+        assert_eq!(
+            objects
+                .into_iter()
+                .filter_dynamic_cast::<::Binding>()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_wrapper_type_name() {
+        assert_eq!(Object::type_name(), "GObject");
+        assert_eq!(::Binding::type_name(), "GBinding");
+        assert!(Object::NONE.is_none());
+
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        let debug = format!("{:?}", obj);
+        assert!(debug.starts_with("Object("));
+        assert!(debug.contains("type: SimpleObject"));
+    }
+
+    #[test]
+    fn test_create_with_class() {
+        let obj = Object::new(ClassObject::get_type(), &[]).expect("Object::new failed");
+        let imp = ClassObject::from_instance(&obj);
+
+        unsafe {
+            let klass = (*(obj.as_ptr() as *const gobject_sys::GTypeInstance)).g_class;
+            assert_eq!(imp.klass as *const (), klass as *const ());
+        }
+    }
+
+    #[test]
+    fn test_c_api_export() {
+        unsafe {
+            assert_eq!(
+                from_glib::<_, Type>(glib_test_child_object_get_type()),
+                ChildObject::get_type()
+            );
+
+            let ptr = glib_test_child_object_new();
+            assert!(!ptr.is_null());
+            let obj: Object = from_glib_full(ptr as *mut gobject_sys::GObject);
+            assert_eq!(obj.get_type(), ChildObject::get_type());
+        }
+    }
+
+    #[test]
+    fn test_override_property() {
+        let obj = Object::new(OverrideObject::get_type(), &[("value", &"foo")])
+            .expect("Object::new failed");
+        assert!(obj.get_type().is_a(&PropertyInterface::static_type()));
+
+        let pspec = obj
+            .find_property("value")
+            .expect("Property 'value' not found");
+        // The interface property is returned instead of the override
+        assert_eq!(pspec.get_owner_type(), PropertyInterface::static_type());
+        assert_eq!(pspec.get_value_type(), String::static_type());
+
+        assert_eq!(
+            obj.get_property("value").unwrap().get::<String>(),
+            Ok(Some("foo".to_string()))
+        );
+        obj.set_property("value", &"bar").unwrap();
+        assert_eq!(
+            obj.get_property("value").unwrap().get::<String>(),
+            Ok(Some("bar".to_string()))
+        );
+        assert_eq!(
+            obj.get_property("other").unwrap().get_some::<bool>(),
+            Ok(true)
+        );
+    }
+
     #[test]
     fn test_set_properties() {
         let obj = Object::new(
@@ -709,6 +1371,203 @@ mod test {
         assert!(name_changed_triggered.load(Ordering::Relaxed));
     }
 
+    #[test]
+    fn test_emission_hook() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let type_ = SimpleObject::get_type();
+        let obj1 = Object::new(type_, &[]).expect("Object::new failed");
+        let obj2 = Object::new(type_, &[]).expect("Object::new failed");
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let count_clone = count.clone();
+        let guard = ::signal_add_emission_hook(type_, "name-changed", move |_hint, args| {
+            let name = args[1]
+                .get::<&str>()
+                .expect("Failed to get args[1]")
+                .expect("Failed to get str from args[1]");
+            assert_eq!(name, "new-name");
+            count_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .expect("Failed to add emission hook");
+
+        obj1.emit("change-name", &[&"new-name"])
+            .expect("Failed to emit");
+        obj2.emit("change-name", &[&"new-name"])
+            .expect("Failed to emit");
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        guard.remove();
+        obj1.emit("change-name", &[&"new-name"])
+            .expect("Failed to emit");
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        assert!(::signal_add_emission_hook(type_, "does-not-exist", |_, _| ()).is_err());
+    }
+
+    #[test]
+    fn test_signal_has_handler_pending() {
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+
+        assert!(!obj
+            .signal_has_handler_pending("name-changed", None, false)
+            .unwrap());
+        assert!(obj
+            .signal_has_handler_pending("does-not-exist", None, false)
+            .is_err());
+
+        let id = obj
+            .connect("name-changed", false, |_| None)
+            .expect("Failed to connect on 'name-changed'");
+        assert!(obj.handler_is_connected(id));
+        assert!(obj
+            .signal_has_handler_pending("name-changed", None, false)
+            .unwrap());
+
+        obj.block_signal(id);
+        assert!(!obj
+            .signal_has_handler_pending("name-changed", None, false)
+            .unwrap());
+        assert!(obj
+            .signal_has_handler_pending("name-changed", None, true)
+            .unwrap());
+        obj.unblock_signal(id);
+
+        obj.connect_notify(Some("name"), |_, _| ());
+        assert!(obj
+            .signal_has_handler_pending("notify::name", None, false)
+            .unwrap());
+        let child = ::Quark::from_string("child");
+        assert!(!obj
+            .signal_has_handler_pending("notify", Some(child), false)
+            .unwrap());
+
+        obj.disconnect(id);
+        assert!(!obj.handler_is_connected(id));
+        assert!(!obj
+            .signal_has_handler_pending("name-changed", None, false)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_disconnect_matched() {
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+
+        // Not tracked
+        obj.connect("create-string", false, |_| None)
+            .expect("Failed to connect on 'create-string'");
+        assert_eq!(obj.disconnect_all(), 0);
+
+        obj.track_signal_handlers();
+
+        obj.connect("name-changed", false, |_| None)
+            .expect("Failed to connect on 'name-changed'");
+        obj.connect("name-changed", true, |_| None)
+            .expect("Failed to connect on 'name-changed'");
+        obj.connect_notify(Some("name"), |_, _| ());
+        obj.connect_notify(None, |_, _| ());
+
+        assert_eq!(obj.disconnect_signal_handlers("notify::name").unwrap(), 1);
+        assert_eq!(obj.disconnect_signal_handlers("name-changed").unwrap(), 2);
+        assert!(obj.disconnect_signal_handlers("does-not-exist").is_err());
+        // Only the remaining handler is still tracked
+        let tracked = unsafe { ::signal::take_tracked_handlers(obj.to_glib_none().0) };
+        assert_eq!(tracked.len(), 1);
+        unsafe { ::signal::track_handler(obj.to_glib_none().0, tracked[0]) };
+        assert!(obj
+            .signal_has_handler_pending("notify", None, true)
+            .unwrap());
+
+        let id = obj
+            .connect("name-changed", false, |_| None)
+            .expect("Failed to connect on 'name-changed'");
+        obj.connect("create-string", false, |_| None)
+            .expect("Failed to connect on 'create-string'");
+        obj.disconnect(id);
+
+        assert_eq!(obj.disconnect_all(), 2);
+        assert!(!obj
+            .signal_has_handler_pending("notify", None, true)
+            .unwrap());
+        // Only the handler connected before tracking was enabled is left
+        assert_eq!(obj.disconnect_signal_handlers("create-string").unwrap(), 1);
+        assert_eq!(obj.disconnect_all(), 0);
+    }
+
+    #[test]
+    fn test_data_typed() {
+        use std::sync::Arc;
+
+        #[derive(Debug, PartialEq)]
+        struct Foo(u32);
+        #[derive(Debug, PartialEq)]
+        struct Bar(u32);
+
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+        assert_eq!(obj.get_data_typed::<Foo>(), None);
+
+        obj.set_data_typed(Foo(1));
+        obj.set_data_typed(Bar(2));
+        assert_eq!(obj.get_data_typed::<Foo>(), Some(Arc::new(Foo(1))));
+        assert_eq!(obj.get_data_typed::<Bar>(), Some(Arc::new(Bar(2))));
+
+        let foo = obj.get_data_typed::<Foo>().unwrap();
+        obj.set_data_typed(Foo(3));
+        assert_eq!(*foo, Foo(1));
+        assert_eq!(obj.get_data_typed::<Foo>(), Some(Arc::new(Foo(3))));
+
+        assert_eq!(obj.steal_data_typed::<Foo>(), Some(Arc::new(Foo(3))));
+        assert_eq!(obj.get_data_typed::<Foo>(), None);
+        assert_eq!(obj.get_data_typed::<Bar>(), Some(Arc::new(Bar(2))));
+    }
+
+    #[test]
+    fn test_emit_typed() {
+        let obj = Object::new(SimpleObject::get_type(), &[("name", &"old-name")])
+            .expect("Object::new failed");
+        let imp = SimpleObject::from_instance(&obj);
+
+        let old_name = imp
+            .emit_typed::<_, String>("change-name", ("new-name",))
+            .expect("Failed to emit");
+        assert_eq!(old_name, "old-name");
+        assert_eq!(*imp.name.borrow(), Some("new-name".to_string()));
+
+        imp.emit_typed::<_, ()>("name-changed", ("foo",))
+            .expect("Failed to emit");
+
+        assert!(imp.emit_typed::<_, String>("create-string", ()).is_err());
+        assert_eq!(
+            imp.emit_typed::<_, Option<String>>("create-string", ())
+                .ok(),
+            Some(None)
+        );
+
+        obj.connect("create-string", false, move |_args| {
+            Some("return value".to_value())
+        })
+        .expect("Failed to connect on 'create-string'");
+        assert_eq!(
+            imp.emit_typed::<_, String>("create-string", ()).ok(),
+            Some("return value".to_string())
+        );
+        assert!(imp.emit_typed::<_, u32>("create-string", ()).is_err());
+    }
+
+    #[test]
+    fn test_typed_class_handler() {
+        let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
+
+        let ret = obj
+            .emit("concat", &[&"ab", &3u32])
+            .expect("Failed to emit")
+            .expect("No return value");
+        assert_eq!(ret.get::<String>(), Ok(Some("ababab".to_string())));
+
+        assert!(obj.emit("concat", &[&"ab", &"cd"]).is_err());
+    }
+
     #[test]
     fn test_signal_return_expected_type() {
         let obj = Object::new(SimpleObject::get_type(), &[]).expect("Object::new failed");
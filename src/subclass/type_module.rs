@@ -0,0 +1,219 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+//! Module that contains all types needed for creating a subclass of `GTypeModule`, e.g. for
+//! implementing loadable modules.
+
+use super::prelude::*;
+use glib_sys;
+use gobject_sys;
+use translate::*;
+use {ObjectClass, TypeModule, TypeModuleClass};
+
+/// Trait for implementors of `glib::TypeModule` subclasses.
+///
+/// `load()` is called whenever the module is first used and should register all types and
+/// interfaces provided by it. `unload()` is called once the module is not used anymore.
+pub trait TypeModuleImpl: ObjectImpl + TypeModuleImplExt {
+    /// Loads the module and registers its types.
+    ///
+    /// Returns `true` if the module was loaded successfully.
+    fn load(&self, type_module: &TypeModule) -> bool;
+
+    /// Unloads the module.
+    ///
+    /// All resources acquired in `load()` should be released here.
+    fn unload(&self, type_module: &TypeModule);
+}
+
+pub trait TypeModuleImplExt {
+    /// Chain up to the parent class' implementation of `glib::TypeModule::load()`.
+    fn parent_load(&self, type_module: &TypeModule) -> bool;
+
+    /// Chain up to the parent class' implementation of `glib::TypeModule::unload()`.
+    fn parent_unload(&self, type_module: &TypeModule);
+}
+
+impl<T: TypeModuleImpl> TypeModuleImplExt for T {
+    fn parent_load(&self, type_module: &TypeModule) -> bool {
+        unsafe {
+            let data = T::type_data();
+            let parent_class =
+                data.as_ref().get_parent_class() as *mut gobject_sys::GTypeModuleClass;
+
+            let f = (*parent_class)
+                .load
+                .expect("No parent class implementation for \"load\"");
+            from_glib(f(type_module.to_glib_none().0))
+        }
+    }
+
+    fn parent_unload(&self, type_module: &TypeModule) {
+        unsafe {
+            let data = T::type_data();
+            let parent_class =
+                data.as_ref().get_parent_class() as *mut gobject_sys::GTypeModuleClass;
+
+            let f = (*parent_class)
+                .unload
+                .expect("No parent class implementation for \"unload\"");
+            f(type_module.to_glib_none().0)
+        }
+    }
+}
+
+unsafe impl<T: TypeModuleImpl> IsSubclassable<T> for TypeModuleClass {
+    fn override_vfuncs(&mut self) {
+        <ObjectClass as IsSubclassable<T>>::override_vfuncs(self);
+        unsafe {
+            let klass = &mut *(self as *mut Self as *mut gobject_sys::GTypeModuleClass);
+            klass.load = Some(load::<T>);
+            klass.unload = Some(unload::<T>);
+        }
+    }
+}
+
+unsafe extern "C" fn load<T: TypeModuleImpl>(
+    type_module: *mut gobject_sys::GTypeModule,
+) -> glib_sys::gboolean {
+    ::guard_ffi(|| {
+        let instance = &*(type_module as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        imp.load(&from_glib_borrow(type_module)).to_glib()
+    })
+}
+
+unsafe extern "C" fn unload<T: TypeModuleImpl>(type_module: *mut gobject_sys::GTypeModule) {
+    ::guard_ffi(|| {
+        let instance = &*(type_module as *mut T::Instance);
+        let imp = instance.get_impl();
+
+        imp.unload(&from_glib_borrow(type_module));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prelude::*;
+    use std::cell::Cell;
+    use subclass;
+    use Object;
+
+    pub struct SimpleModule {
+        loaded: Cell<u32>,
+        unloaded: Cell<u32>,
+    }
+
+    impl ObjectSubclass for SimpleModule {
+        const NAME: &'static str = "SimpleModule";
+        type ParentType = TypeModule;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            SimpleModule {
+                loaded: Cell::new(0),
+                unloaded: Cell::new(0),
+            }
+        }
+    }
+
+    impl ObjectImpl for SimpleModule {}
+
+    impl TypeModuleImpl for SimpleModule {
+        fn load(&self, type_module: &TypeModule) -> bool {
+            self.loaded.set(self.loaded.get() + 1);
+            subclass::register_dynamic_type::<DynamicObject>(type_module).is_ok()
+        }
+
+        fn unload(&self, _type_module: &TypeModule) {
+            self.unloaded.set(self.unloaded.get() + 1);
+        }
+    }
+
+    pub struct DynamicObject;
+
+    impl ObjectSubclass for DynamicObject {
+        const NAME: &'static str = "DynamicObject";
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            DynamicObject
+        }
+    }
+
+    impl ObjectImpl for DynamicObject {}
+
+    pub struct StaticObject;
+
+    impl ObjectSubclass for StaticObject {
+        const NAME: &'static str = "StaticObject";
+        type ParentType = Object;
+        type Instance = subclass::simple::InstanceStruct<Self>;
+        type Class = subclass::simple::ClassStruct<Self>;
+
+        glib_object_subclass!();
+
+        fn new() -> Self {
+            StaticObject
+        }
+    }
+
+    impl ObjectImpl for StaticObject {}
+
+    #[test]
+    fn test_load_unload() {
+        let obj = Object::new(SimpleModule::get_type(), &[])
+            .expect("Object::new failed")
+            .downcast::<TypeModule>()
+            .expect("Not a TypeModule");
+        obj.set_name("simple-module");
+        let imp = SimpleModule::from_instance(&obj);
+
+        assert!(obj.use_());
+        assert_eq!(imp.loaded.get(), 1);
+        assert!(obj.use_());
+        assert_eq!(imp.loaded.get(), 1);
+
+        obj.unuse();
+        assert_eq!(imp.unloaded.get(), 0);
+        obj.unuse();
+        assert_eq!(imp.unloaded.get(), 1);
+
+        assert!(obj.use_());
+        assert_eq!(imp.loaded.get(), 2);
+        obj.unuse();
+        assert_eq!(imp.unloaded.get(), 2);
+
+        let type_ = DynamicObject::get_type();
+        assert_eq!(type_.name(), "DynamicObject");
+        assert!(type_.is_a(&Object::static_type()));
+
+        assert!(obj.use_());
+        let dynamic = Object::new(type_, &[]).expect("Object::new failed");
+        assert_eq!(dynamic.get_type(), type_);
+        drop(dynamic);
+        obj.unuse();
+    }
+
+    #[test]
+    fn test_register_static_type() {
+        let type_ = StaticObject::get_type();
+
+        let obj = Object::new(SimpleModule::get_type(), &[])
+            .expect("Object::new failed")
+            .downcast::<TypeModule>()
+            .expect("Not a TypeModule");
+        assert!(subclass::register_dynamic_type::<StaticObject>(&obj).is_err());
+        assert_eq!(StaticObject::get_type(), type_);
+    }
+}
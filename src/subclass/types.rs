@@ -6,13 +6,17 @@
 
 use glib_sys;
 use gobject_sys;
+use libc::c_uint;
 use object::{ObjectExt, ObjectType};
+use signal::{SignalHandlerArgs, SignalHandlerReturn};
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::marker;
 use std::mem;
 use std::ptr;
+use std::slice;
 use translate::*;
-use {Closure, IsA, IsClassFor, SignalFlags, StaticType, Type, Value};
+use {BoolError, Closure, IsA, IsClassFor, SignalFlags, StaticType, Type, TypeModule, Value};
 
 /// A newly registered `glib::Type` that is currently still being initialized.
 ///
@@ -207,8 +211,11 @@ macro_rules! glib_object_subclass {
         fn get_type() -> $crate::Type {
             static ONCE: ::std::sync::Once = ::std::sync::Once::new();
 
-            ONCE.call_once(|| {
-                $crate::subclass::register_type::<Self>();
+            ONCE.call_once(|| unsafe {
+                // Dynamic types are registered by their `TypeModule` instead.
+                if Self::type_data().as_ref().get_type() == $crate::Type::Invalid {
+                    $crate::subclass::register_type::<Self>();
+                }
             });
 
             unsafe {
@@ -222,6 +229,57 @@ macro_rules! glib_object_subclass {
     };
 }
 
+#[macro_export]
+/// Macro for exporting an [`ObjectSubclass`] to C.
+///
+/// Generates a `#[no_mangle] extern "C"` function returning the `GType` of the subclass and,
+/// optionally, one creating a new instance without any properties set. The latter returns a
+/// full reference or `NULL` if the type is abstract or could not be instantiated.
+///
+/// ```ignore
+/// glib_c_api_export!(MyObject, get_type => my_object_get_type, new => my_object_new);
+/// ```
+///
+/// The corresponding C declarations would be:
+///
+/// ```c
+/// GType my_object_get_type (void);
+/// MyObject *my_object_new (void);
+/// ```
+///
+/// [`ObjectSubclass`]: subclass/types/trait.ObjectSubclass.html
+macro_rules! glib_c_api_export {
+    ($name:ty, get_type => $get_type:ident) => {
+        #[no_mangle]
+        pub extern "C" fn $get_type() -> $crate::glib_sys::GType {
+            $crate::translate::ToGlib::to_glib(
+                &<$name as $crate::subclass::types::ObjectSubclass>::get_type(),
+            )
+        }
+    };
+
+    ($name:ty, get_type => $get_type:ident, new => $new:ident) => {
+        $crate::glib_c_api_export!($name, get_type => $get_type);
+
+        #[no_mangle]
+        pub extern "C" fn $new() -> *mut <$name as $crate::subclass::types::ObjectSubclass>::Instance {
+            if <$name as $crate::subclass::types::ObjectSubclass>::ABSTRACT {
+                return ::std::ptr::null_mut();
+            }
+
+            let type_ = <$name as $crate::subclass::types::ObjectSubclass>::get_type();
+            match $crate::Object::new(type_, &[]) {
+                Ok(obj) => {
+                    let ptr: *mut $crate::gobject_sys::GObject =
+                        $crate::translate::ToGlibPtr::to_glib_full(&obj);
+                    ptr as *mut _
+                }
+                Err(_) => ::std::ptr::null_mut(),
+            }
+        }
+    };
+}
+
 /// The central trait for subclassing a `GObject` type.
 ///
 /// Links together the type name, parent type and the instance and
@@ -235,9 +293,24 @@ macro_rules! glib_object_subclass {
 pub trait ObjectSubclass: Sized + 'static {
     /// `GObject` type name.
     ///
-    /// This must be unique in the whole process.
+    /// This must be unique in the whole process, unless `ALLOW_NAME_CONFLICT` is set.
     const NAME: &'static str;
 
+    /// Allow name conflicts for this type.
+    ///
+    /// By default registering a type whose `NAME` is already in use panics. If this is set to
+    /// `true` a numeric suffix is appended to the name instead, e.g. `MyObject-1`, until a free
+    /// name is found. This is useful if the same type can end up being registered multiple
+    /// times, e.g. if it is part of a library that is linked into multiple plugins.
+    ///
+    /// Use [`get_type`] to retrieve the registered type instead of looking it up by name in
+    /// that case.
+    ///
+    /// Optional.
+    ///
+    /// [`get_type`]: #tymethod.get_type
+    const ALLOW_NAME_CONFLICT: bool = false;
+
     /// If this subclass is an abstract class or not.
     ///
     /// By default all subclasses are non-abstract types but setting this to `true` will create an
@@ -381,73 +454,79 @@ unsafe extern "C" fn class_init<T: ObjectSubclass>(
 ) where
     <<T as ObjectSubclass>::ParentType as ObjectType>::RustClassType: IsSubclassable<T>,
 {
-    let mut data = T::type_data();
+    ::guard_ffi(|| {
+        let mut data = T::type_data();
 
-    // We have to update the private struct offset once the class is actually
-    // being initialized.
-    if mem::size_of::<T>() != 0 {
-        let mut private_offset = data.as_ref().private_offset as i32;
-        gobject_sys::g_type_class_adjust_private_offset(klass, &mut private_offset);
-        (*data.as_mut()).private_offset = private_offset as isize;
-    }
+        // We have to update the private struct offset once the class is actually
+        // being initialized.
+        if mem::size_of::<T>() != 0 {
+            let mut private_offset = data.as_ref().private_offset as i32;
+            gobject_sys::g_type_class_adjust_private_offset(klass, &mut private_offset);
+            (*data.as_mut()).private_offset = private_offset as isize;
+        }
 
-    // Set trampolines for the basic GObject virtual methods.
-    {
-        let gobject_klass = &mut *(klass as *mut gobject_sys::GObjectClass);
+        // Set trampolines for the basic GObject virtual methods.
+        {
+            let gobject_klass = &mut *(klass as *mut gobject_sys::GObjectClass);
 
-        gobject_klass.finalize = Some(finalize::<T>);
-    }
+            gobject_klass.finalize = Some(finalize::<T>);
+        }
 
-    // And finally peek the parent class struct (containing the parent class'
-    // implementations of virtual methods for chaining up), and call the subclass'
-    // class initialization function.
-    {
-        let klass = &mut *(klass as *mut T::Class);
-        let parent_class =
-            gobject_sys::g_type_class_peek_parent(klass as *mut _ as glib_sys::gpointer)
-                as *mut <T::ParentType as ObjectType>::GlibClassType;
-        assert!(!parent_class.is_null());
+        // And finally peek the parent class struct (containing the parent class'
+        // implementations of virtual methods for chaining up), and call the subclass'
+        // class initialization function.
+        {
+            let klass = &mut *(klass as *mut T::Class);
+            let parent_class =
+                gobject_sys::g_type_class_peek_parent(klass as *mut _ as glib_sys::gpointer)
+                    as *mut <T::ParentType as ObjectType>::GlibClassType;
+            assert!(!parent_class.is_null());
 
-        (*data.as_mut()).parent_class = parent_class as glib_sys::gpointer;
+            (*data.as_mut()).parent_class = parent_class as glib_sys::gpointer;
 
-        klass.override_vfuncs();
-        T::class_init(klass);
-    }
+            klass.override_vfuncs();
+            T::class_init(klass);
+        }
+    })
 }
 
 unsafe extern "C" fn instance_init<T: ObjectSubclass>(
     obj: *mut gobject_sys::GTypeInstance,
     klass: glib_sys::gpointer,
 ) {
-    // Get offset to the storage of our private struct, create it
-    // and actually store it in that place.
-    let mut data = T::type_data();
-    let private_offset = (*data.as_mut()).private_offset;
-    let ptr: *mut u8 = obj as *mut _ as *mut u8;
-    let priv_ptr = ptr.offset(private_offset);
-    let imp_storage = priv_ptr as *mut T;
+    ::guard_ffi(|| {
+        // Get offset to the storage of our private struct, create it
+        // and actually store it in that place.
+        let mut data = T::type_data();
+        let private_offset = (*data.as_mut()).private_offset;
+        let ptr: *mut u8 = obj as *mut _ as *mut u8;
+        let priv_ptr = ptr.offset(private_offset);
+        let imp_storage = priv_ptr as *mut T;
 
-    let klass = &*(klass as *const T::Class);
+        let klass = &*(klass as *const T::Class);
 
-    let imp = T::with_class(klass);
+        let imp = T::with_class(klass);
 
-    ptr::write(imp_storage, imp);
+        ptr::write(imp_storage, imp);
+    })
 }
 
 unsafe extern "C" fn finalize<T: ObjectSubclass>(obj: *mut gobject_sys::GObject) {
-    // Retrieve the private struct and drop it for freeing all associated memory.
-    let mut data = T::type_data();
-    let private_offset = (*data.as_mut()).private_offset;
-    let ptr: *mut u8 = obj as *mut _ as *mut u8;
-    let priv_ptr = ptr.offset(private_offset);
-    let imp_storage = priv_ptr as *mut T;
-    ptr::drop_in_place(imp_storage);
-
-    // Chain up to the parent class' finalize implementation, if any.
-    let parent_class = &*(data.as_ref().get_parent_class() as *const gobject_sys::GObjectClass);
-    if let Some(ref func) = parent_class.finalize {
-        func(obj);
-    }
+    ::guard_ffi(|| {
+        // Retrieve the private struct and drop it for freeing all associated memory.
+        let mut data = T::type_data();
+        let private_offset = (*data.as_mut()).private_offset;
+        let ptr: *mut u8 = obj as *mut _ as *mut u8;
+        let priv_ptr = ptr.offset(private_offset);
+        let imp_storage = priv_ptr as *mut T;
+        ptr::drop_in_place(imp_storage);
+
+        // Chain up to the parent class' finalize implementation, if any.
+        let parent_class = &*(data.as_ref().get_parent_class() as *const gobject_sys::GObjectClass);
+        if let Some(ref func) = parent_class.finalize {
+            func(obj);
+        }
+    })
 }
 
 /// Register a `glib::Type` ID for `T`.
@@ -462,26 +541,10 @@ pub fn register_type<T: ObjectSubclass>() -> Type
 where
     <<T as ObjectSubclass>::ParentType as ObjectType>::RustClassType: IsSubclassable<T>,
 {
-    // GLib aligns the type private data to two gsizes so we can't safely store any type there that
-    // requires a bigger alignment.
-    if mem::align_of::<T>() > 2 * mem::size_of::<usize>() {
-        panic!(
-            "Alignment {} of type not supported, bigger than {}",
-            mem::align_of::<T>(),
-            2 * mem::size_of::<usize>(),
-        );
-    }
+    check_alignment::<T>();
 
     unsafe {
-        use std::ffi::CString;
-
-        let type_name = CString::new(T::NAME).unwrap();
-        if gobject_sys::g_type_from_name(type_name.as_ptr()) != gobject_sys::G_TYPE_INVALID {
-            panic!(
-                "Type {} has already been registered",
-                type_name.to_str().unwrap()
-            );
-        }
+        let type_name = new_type_name::<T>();
 
         let type_ = from_glib(gobject_sys::g_type_register_static_simple(
             <T::ParentType as StaticType>::static_type().to_glib(),
@@ -513,6 +576,140 @@ where
     }
 }
 
+/// Register a dynamic `glib::Type` ID for `T` with `type_module`.
+///
+/// This is meant to be called from [`TypeModuleImpl::load()`] every time the module is loaded.
+/// The first call registers the type and runs [`ObjectSubclass::type_init()`], later calls only
+/// re-register the same type with the module as required by `GTypeModule`.
+///
+/// Once registered, `T::get_type()` returns the dynamic type instead of registering a static one.
+/// If `T::get_type()` was already called before, `T` is registered as a static type and can't
+/// be registered with the module anymore. The same applies if `T` was registered with a
+/// different module. An error is returned in both cases.
+///
+/// [`TypeModuleImpl::load()`]: ../type_module/trait.TypeModuleImpl.html#tymethod.load
+/// [`ObjectSubclass::type_init()`]: trait.ObjectSubclass.html#method.type_init
+pub fn register_dynamic_type<T: ObjectSubclass>(type_module: &TypeModule) -> Result<Type, BoolError>
+where
+    <<T as ObjectSubclass>::ParentType as ObjectType>::RustClassType: IsSubclassable<T>,
+{
+    check_alignment::<T>();
+
+    assert!(
+        mem::size_of::<T::Class>() <= ::std::u16::MAX as usize
+            && mem::size_of::<T::Instance>() <= ::std::u16::MAX as usize,
+        "Class or instance struct too big for a dynamic type"
+    );
+
+    unsafe {
+        let mut data = T::type_data();
+        let registered = data.as_ref().get_type() != Type::Invalid;
+
+        if registered {
+            let plugin = gobject_sys::g_type_get_plugin(data.as_ref().get_type().to_glib());
+            if plugin.is_null() {
+                return Err(glib_bool_error!(
+                    "Type {} is already registered as static type",
+                    data.as_ref().get_type().name()
+                ));
+            } else if plugin != type_module.as_ptr() as *mut gobject_sys::GTypePlugin {
+                return Err(glib_bool_error!(
+                    "Type {} is already registered with a different module",
+                    data.as_ref().get_type().name()
+                ));
+            }
+        }
+
+        let type_name = if registered {
+            CStr::from_ptr(gobject_sys::g_type_name(data.as_ref().get_type().to_glib())).to_owned()
+        } else {
+            new_type_name::<T>()
+        };
+
+        let type_info = gobject_sys::GTypeInfo {
+            class_size: mem::size_of::<T::Class>() as u16,
+            base_init: None,
+            base_finalize: None,
+            class_init: Some(class_init::<T>),
+            class_finalize: None,
+            class_data: ptr::null(),
+            instance_size: mem::size_of::<T::Instance>() as u16,
+            n_preallocs: 0,
+            instance_init: Some(instance_init::<T>),
+            value_table: ptr::null(),
+        };
+
+        let type_: Type = from_glib(gobject_sys::g_type_module_register_type(
+            type_module.to_glib_none().0,
+            <T::ParentType as StaticType>::static_type().to_glib(),
+            type_name.as_ptr(),
+            &type_info,
+            if T::ABSTRACT {
+                gobject_sys::G_TYPE_FLAG_ABSTRACT
+            } else {
+                0
+            },
+        ));
+        if type_ == Type::Invalid {
+            return Err(glib_bool_error!(
+                "Failed to register dynamic type {}",
+                type_name.to_string_lossy()
+            ));
+        }
+
+        if !registered {
+            (*data.as_mut()).type_ = type_;
+
+            // g_type_add_instance_private() can't be used for dynamic types. Store the size
+            // instead, class_init() turns it into the actual offset.
+            (*data.as_mut()).private_offset = mem::size_of::<T>() as isize;
+
+            T::type_init(&mut InitializingType::<T>(type_, marker::PhantomData));
+        }
+
+        Ok(type_)
+    }
+}
+
+// GLib aligns the type private data to two gsizes so we can't safely store any type there that
+// requires a bigger alignment.
+fn check_alignment<T>() {
+    if mem::align_of::<T>() > 2 * mem::size_of::<usize>() {
+        panic!(
+            "Alignment {} of type not supported, bigger than {}",
+            mem::align_of::<T>(),
+            2 * mem::size_of::<usize>(),
+        );
+    }
+}
+
+unsafe fn new_type_name<T: ObjectSubclass>() -> CString {
+    if T::ALLOW_NAME_CONFLICT {
+        let mut i = 0;
+        loop {
+            let type_name = if i == 0 {
+                CString::new(T::NAME).unwrap()
+            } else {
+                CString::new(format!("{}-{}", T::NAME, i)).unwrap()
+            };
+            if gobject_sys::g_type_from_name(type_name.as_ptr()) == gobject_sys::G_TYPE_INVALID {
+                break type_name;
+            }
+            i += 1;
+        }
+    } else {
+        let type_name = CString::new(T::NAME).unwrap();
+        if gobject_sys::g_type_from_name(type_name.as_ptr()) != gobject_sys::G_TYPE_INVALID {
+            panic!(
+                "Type {} has already been registered",
+                type_name.to_str().unwrap()
+            );
+        }
+
+        type_name
+    }
+}
+
 pub(crate) unsafe fn add_signal(
     type_: glib_sys::GType,
     name: &str,
@@ -580,13 +777,15 @@ pub(crate) unsafe fn add_signal_with_accumulator<F>(
         handler_return: *const gobject_sys::GValue,
         data: glib_sys::gpointer,
     ) -> glib_sys::gboolean {
-        let accumulator: &F = &*(data as *const &F);
-        accumulator(
-            &*(ihint as *const SignalInvocationHint),
-            &mut *(return_accu as *mut Value),
-            &*(handler_return as *const Value),
-        )
-        .to_glib()
+        ::guard_ffi(|| {
+            let accumulator: &F = &*(data as *const &F);
+            accumulator(
+                &*(ihint as *const SignalInvocationHint),
+                &mut *(return_accu as *mut Value),
+                &*(handler_return as *const Value),
+            )
+            .to_glib()
+        })
     }
 
     gobject_sys::g_signal_newv(
@@ -603,6 +802,11 @@ pub(crate) unsafe fn add_signal_with_accumulator<F>(
     );
 }
 
+/// Token passed to signal class handlers.
+///
+/// It identifies the instance the signal is emitted on and is required for chaining up to the
+/// overridden class handler with the `&[Value]` the class handler was called with. These values
+/// are a view of the C array of `GValue`s of the emission and are not copied.
 pub struct SignalClassHandlerToken(*mut gobject_sys::GTypeInstance);
 
 impl fmt::Debug for SignalClassHandlerToken {
@@ -643,6 +847,68 @@ pub(crate) unsafe fn add_signal_with_class_handler<F>(
     );
 }
 
+pub(crate) unsafe fn add_signal_with_typed_class_handler<Args, Ret, F>(
+    type_: glib_sys::GType,
+    name: &str,
+    flags: SignalFlags,
+    class_handler: F,
+) where
+    Args: SignalHandlerArgs,
+    Ret: SignalHandlerReturn,
+    F: Fn(&SignalClassHandlerToken, Args) -> Ret + Send + Sync + 'static,
+{
+    // Marshaller specific to the signature of the class handler: the arguments are extracted
+    // directly from the emission's values and the return value is stored directly in the
+    // emission's return value, without going through `Option<Value>`.
+    unsafe extern "C" fn marshal<Args, Ret, F>(
+        _closure: *mut gobject_sys::GClosure,
+        return_value: *mut gobject_sys::GValue,
+        n_param_values: c_uint,
+        param_values: *const gobject_sys::GValue,
+        _invocation_hint: glib_sys::gpointer,
+        marshal_data: glib_sys::gpointer,
+    ) where
+        Args: SignalHandlerArgs,
+        Ret: SignalHandlerReturn,
+        F: Fn(&SignalClassHandlerToken, Args) -> Ret,
+    {
+        ::guard_ffi(|| {
+            let values: &[Value] =
+                slice::from_raw_parts(param_values as *const _, n_param_values as usize);
+            let class_handler: &F = &*(marshal_data as *const F);
+
+            let instance = gobject_sys::g_value_get_object(param_values);
+            let ret = class_handler(
+                &SignalClassHandlerToken(instance as *mut _),
+                Args::from_values(&values[1..]),
+            );
+
+            if !return_value.is_null() {
+                ret.set_return_value(&mut *(return_value as *mut Value));
+            }
+        })
+    }
+
+    let arg_types = Args::arg_types()
+        .iter()
+        .map(ToGlib::to_glib)
+        .collect::<Vec<_>>();
+    let class_handler = Closure::with_marshal(class_handler, Some(marshal::<Args, Ret, F>));
+
+    gobject_sys::g_signal_newv(
+        name.to_glib_none().0,
+        type_,
+        flags.to_glib(),
+        class_handler.to_glib_none().0,
+        None,
+        ptr::null_mut(),
+        None,
+        Ret::return_type().to_glib(),
+        arg_types.len() as u32,
+        arg_types.as_ptr() as *mut _,
+    );
+}
+
 pub(crate) unsafe fn add_signal_with_class_handler_and_accumulator<F, G>(
     type_: glib_sys::GType,
     name: &str,
@@ -671,13 +937,15 @@ pub(crate) unsafe fn add_signal_with_class_handler_and_accumulator<F, G>(
         handler_return: *const gobject_sys::GValue,
         data: glib_sys::gpointer,
     ) -> glib_sys::gboolean {
-        let accumulator: &G = &*(data as *const &G);
-        accumulator(
-            &SignalInvocationHint(*ihint),
-            &mut *(return_accu as *mut Value),
-            &*(handler_return as *const Value),
-        )
-        .to_glib()
+        ::guard_ffi(|| {
+            let accumulator: &G = &*(data as *const &G);
+            accumulator(
+                &SignalInvocationHint(*ihint),
+                &mut *(return_accu as *mut Value),
+                &*(handler_return as *const Value),
+            )
+            .to_glib()
+        })
     }
 
     gobject_sys::g_signal_newv(
@@ -728,7 +996,7 @@ pub(crate) unsafe fn signal_chain_from_overridden(
     values: &[Value],
 ) -> Option<Value> {
     assert_eq!(instance, token.0);
-    let mut result = Value::uninitialized();
+    let mut result = Value::new_empty();
     gobject_sys::g_signal_chain_from_overridden(
         values.as_ptr() as *mut Value as *mut gobject_sys::GValue,
         result.to_glib_none_mut().0,
@@ -170,8 +170,10 @@ impl Drop for ThreadPool {
 }
 
 unsafe extern "C" fn spawn_func(func: glib_sys::gpointer, _data: glib_sys::gpointer) {
-    let func: Box<Box<dyn FnOnce()>> = Box::from_raw(func as *mut _);
-    func()
+    ::guard_ffi(|| {
+        let func: Box<Box<dyn FnOnce()>> = Box::from_raw(func as *mut _);
+        func()
+    })
 }
 
 #[cfg(test)]
@@ -0,0 +1,364 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+use futures_channel::mpsc;
+use futures_core::stream::Stream;
+use futures_core::task;
+use futures_core::task::Poll;
+use futures_util::stream::StreamExt;
+use std::cell::{Cell, RefCell};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
+
+use get_monotonic_time;
+use object::{ObjectType, WeakRef};
+use Continue;
+use MainContext;
+use Source;
+use ThreadGuard;
+
+/// Information about a single tick, passed to tick callbacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickInfo {
+    /// Number of the tick.
+    ///
+    /// The first tick has number 1 and happens one tick interval after the callback was added.
+    /// Skipped ticks are counted too, so this can be used to compute the expected time of the
+    /// tick.
+    pub frame: u64,
+    /// Monotonic time in microseconds at which the tick was dispatched, see
+    /// [`get_monotonic_time`](fn.get_monotonic_time.html).
+    pub frame_time: i64,
+    /// Time since the previous tick.
+    pub delta: Duration,
+    /// Number of ticks that were skipped since the previous tick because the main loop was
+    /// busy for longer than the tick interval.
+    pub skipped: u64,
+}
+
+struct TickShared {
+    source: RefCell<Option<Source>>,
+    stopped: Cell<bool>,
+}
+
+/// Handle of a tick callback added with [`add_tick_callback`].
+///
+/// Dropping the handle does not remove the callback.
+///
+/// [`add_tick_callback`]: fn.add_tick_callback.html
+pub struct TickCallbackId(Rc<TickShared>);
+
+impl TickCallbackId {
+    /// Removes the tick callback.
+    ///
+    /// This does nothing if the callback was removed already.
+    pub fn remove(&self) {
+        self.0.stopped.set(true);
+        if let Some(source) = self.0.source.borrow_mut().take() {
+            source.destroy();
+        }
+    }
+
+    /// Returns `true` if the tick callback is still active.
+    pub fn is_active(&self) -> bool {
+        !self.0.stopped.get()
+    }
+}
+
+struct TickState<F> {
+    func: F,
+    interval: i64,
+    start_time: i64,
+    last_time: i64,
+    frame: u64,
+    context: MainContext,
+}
+
+fn schedule<F: FnMut(&TickInfo) -> Continue + 'static>(
+    state: Rc<RefCell<TickState<F>>>,
+    shared: Rc<TickShared>,
+) {
+    let (next_time, context) = {
+        let state = state.borrow();
+        (
+            state.start_time + state.frame as i64 * state.interval,
+            state.context.clone(),
+        )
+    };
+
+    // Timeouts have millisecond granularity, round up so that ticks never happen early
+    let delay = (next_time - get_monotonic_time()).max(0) as u64;
+    let delay = Duration::from_millis((delay + 999) / 1000);
+
+    let guard = ThreadGuard::new((state, shared.clone()));
+    let source = ::timeout_source_new(delay, Some("glib-rs tick"), ::PRIORITY_DEFAULT, move || {
+        let (ref state, ref shared) = *guard.get_ref();
+        dispatch(state.clone(), shared.clone());
+        Continue(false)
+    });
+    source.attach(Some(&context));
+    *shared.source.borrow_mut() = Some(source);
+}
+
+fn dispatch<F: FnMut(&TickInfo) -> Continue + 'static>(
+    state: Rc<RefCell<TickState<F>>>,
+    shared: Rc<TickShared>,
+) {
+    let _ = shared.source.borrow_mut().take();
+    if shared.stopped.get() {
+        return;
+    }
+
+    let now = get_monotonic_time();
+    let info = {
+        let mut state = state.borrow_mut();
+
+        // Skip all ticks whose time has passed already instead of trying to catch up, but keep
+        // the tick times aligned to the start time so that there is no drift
+        let current_frame = ((now - state.start_time) / state.interval) as u64;
+        let skipped = current_frame.saturating_sub(state.frame);
+        state.frame += skipped;
+
+        let info = TickInfo {
+            frame: state.frame,
+            frame_time: now,
+            delta: Duration::from_micros((now - state.last_time).max(0) as u64),
+            skipped,
+        };
+        state.last_time = now;
+        state.frame += 1;
+
+        info
+    };
+
+    let res = {
+        let mut state = state.borrow_mut();
+        (state.func)(&info)
+    };
+
+    if res.0 && !shared.stopped.get() {
+        schedule(state, shared);
+    } else {
+        shared.stopped.set(true);
+    }
+}
+
+/// Adds a closure to be called `fps` times per second by the thread default main context.
+///
+/// The tick times are computed relative to the time the callback was added, so delays of single
+/// ticks don't accumulate over time. If the main context is busy for longer than a tick
+/// interval the missed ticks are skipped and reported in [`TickInfo::skipped`].
+///
+/// `func` will be called until it returns `Continue(false)` or the callback is removed with
+/// [`TickCallbackId::remove`].
+///
+/// This function panics if called from a different thread than the one that owns the thread
+/// default main context, or if `fps` is not a positive number.
+///
+/// [`TickInfo::skipped`]: struct.TickInfo.html#structfield.skipped
+/// [`TickCallbackId::remove`]: struct.TickCallbackId.html#method.remove
+pub fn add_tick_callback<F>(fps: f64, func: F) -> TickCallbackId
+where
+    F: FnMut(&TickInfo) -> Continue + 'static,
+{
+    assert!(
+        fps > 0.0 && fps.is_finite(),
+        "Invalid number of ticks per second {}",
+        fps
+    );
+
+    let context = MainContext::ref_thread_default();
+    assert!(
+        context.is_owner(),
+        "Tick callbacks can only be added from the thread owning the MainContext"
+    );
+
+    let now = get_monotonic_time();
+    let state = Rc::new(RefCell::new(TickState {
+        func,
+        interval: ((1_000_000.0 / fps).round() as i64).max(1),
+        start_time: now,
+        last_time: now,
+        frame: 1,
+        context,
+    }));
+    let shared = Rc::new(TickShared {
+        source: RefCell::new(None),
+        stopped: Cell::new(false),
+    });
+
+    schedule(state, shared.clone());
+
+    TickCallbackId(shared)
+}
+
+/// Adds a closure to be called `fps` times per second for as long as `target` is alive.
+///
+/// This is the same as [`add_tick_callback`] but the callback is automatically removed once
+/// the object referenced by `target` is finalized, and the object is passed to `func`.
+///
+/// [`add_tick_callback`]: fn.add_tick_callback.html
+pub fn add_tick_callback_for<T, F>(target: &WeakRef<T>, fps: f64, mut func: F) -> TickCallbackId
+where
+    T: ObjectType,
+    F: FnMut(&T, &TickInfo) -> Continue + 'static,
+{
+    let target = target.clone();
+    add_tick_callback(fps, move |info| match target.upgrade() {
+        Some(target) => func(&target, info),
+        None => Continue(false),
+    })
+}
+
+struct TickStream {
+    fps: f64,
+    tick: Option<(TickCallbackId, mpsc::UnboundedReceiver<TickInfo>)>,
+}
+
+impl Stream for TickStream {
+    type Item = TickInfo;
+
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut task::Context) -> Poll<Option<TickInfo>> {
+        let fps = self.fps;
+        let &mut (_, ref mut receiver) = self.tick.get_or_insert_with(|| {
+            let (send, recv) = mpsc::unbounded();
+            let id = add_tick_callback(fps, move |info| {
+                Continue(send.unbounded_send(*info).is_ok())
+            });
+            (id, recv)
+        });
+
+        receiver.poll_next_unpin(ctx)
+    }
+}
+
+impl Drop for TickStream {
+    fn drop(&mut self) {
+        if let Some((id, _)) = self.tick.take() {
+            id.remove();
+        }
+    }
+}
+
+/// Create a `Stream` that will provide a [`TickInfo`] `fps` times per second.
+///
+/// See [`add_tick_callback`] for details.
+///
+/// The `Stream` must be spawned on an `Executor` backed by the thread default
+/// `glib::MainContext`.
+///
+/// [`TickInfo`]: struct.TickInfo.html
+/// [`add_tick_callback`]: fn.add_tick_callback.html
+pub fn tick_stream(fps: f64) -> Pin<Box<dyn Stream<Item = TickInfo> + 'static>> {
+    Box::pin(TickStream { fps, tick: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MainLoop;
+    use Object;
+    use ObjectExt;
+    use StaticType;
+
+    #[test]
+    fn test_tick_callback() {
+        let c = MainContext::new();
+        c.push_thread_default();
+        let l = MainLoop::new(Some(&c), false);
+
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let ticks_clone = ticks.clone();
+        let l_clone = l.clone();
+        let id = add_tick_callback(100.0, move |info| {
+            ticks_clone.borrow_mut().push(*info);
+            if ticks_clone.borrow().len() == 5 {
+                l_clone.quit();
+                Continue(false)
+            } else {
+                Continue(true)
+            }
+        });
+        assert!(id.is_active());
+
+        l.run();
+        c.pop_thread_default();
+
+        assert!(!id.is_active());
+        let ticks = ticks.borrow();
+        assert_eq!(ticks.len(), 5);
+        let mut frame = 0;
+        for info in ticks.iter() {
+            assert_eq!(info.frame, frame + 1 + info.skipped);
+            frame = info.frame;
+        }
+    }
+
+    #[test]
+    fn test_tick_callback_remove() {
+        let c = MainContext::new();
+        c.push_thread_default();
+        let l = MainLoop::new(Some(&c), false);
+
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let id = Rc::new(RefCell::new(None::<TickCallbackId>));
+        let id_clone = id.clone();
+        let l_clone = l.clone();
+        *id.borrow_mut() = Some(add_tick_callback(100.0, move |_| {
+            count_clone.set(count_clone.get() + 1);
+            if let Some(ref id) = *id_clone.borrow() {
+                id.remove();
+            }
+            l_clone.quit();
+            Continue(true)
+        }));
+
+        l.run();
+        c.pop_thread_default();
+
+        assert_eq!(count.get(), 1);
+        assert!(!id.borrow().as_ref().unwrap().is_active());
+    }
+
+    #[test]
+    fn test_tick_callback_for() {
+        let c = MainContext::new();
+        c.push_thread_default();
+
+        let obj = Rc::new(RefCell::new(Some(
+            Object::new(Object::static_type(), &[]).unwrap(),
+        )));
+        let weak = obj.borrow().as_ref().unwrap().downgrade();
+
+        let count = Rc::new(Cell::new(0));
+        let count_clone = count.clone();
+        let obj_clone = obj.clone();
+        let id = add_tick_callback_for(&weak, 100.0, move |_, _| {
+            count_clone.set(count_clone.get() + 1);
+            // Drop the only strong reference after the second tick
+            if count_clone.get() == 2 {
+                obj_clone.borrow_mut().take();
+            }
+            Continue(true)
+        });
+
+        while id.is_active() {
+            c.iteration(true);
+        }
+        c.pop_thread_default();
+
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_tick_stream() {
+        let c = MainContext::new();
+
+        let res = c.block_on(tick_stream(100.0).take(3).collect::<Vec<_>>());
+        assert_eq!(res.len(), 3);
+        assert!(res[0].frame < res[1].frame && res[1].frame < res[2].frame);
+    }
+}
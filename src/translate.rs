@@ -64,19 +64,44 @@
 //!         }
 //!     }
 //! ```
+//!
+//! Newtypes around existing wrapper types usually only need to forward these traits to the
+//! wrapped value. Instead of implementing `StaticType`, `GlibPtrDefault`, `ToGlibPtr`,
+//! `FromGlibPtrNone`, `FromGlibPtrFull`, `FromGlibPtrBorrow` and the `Value` traits manually,
+//! use `#[derive(TranslateNewtype)]`:
+//!
+//! ```
+//! use glib::prelude::*;
+//! use glib::translate::*;
+//! use glib::TranslateNewtype;
+//!
+//! #[derive(Clone, Debug, TranslateNewtype)]
+//! struct Context(glib::MainContext);
+//!
+//! let context = Context(glib::MainContext::new());
+//! let ptr: *mut glib::glib_sys::GMainContext = context.to_glib_none().0;
+//! let other: Context = unsafe { from_glib_none(ptr) };
+//! assert_eq!(other.0, context.0);
+//! ```
 
 use glib_sys;
 use libc::{c_char, size_t};
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
 use std::char;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
 use std::mem;
 #[cfg(not(windows))]
 use std::os::unix::prelude::*;
 use std::path::{Path, PathBuf};
 use std::ptr;
+use std::sync::{Arc, Mutex};
 
 /// A pointer
 pub trait Ptr: Copy + 'static {
@@ -134,6 +159,62 @@ pub fn const_override<T>(ptr: *mut T) -> *const T {
     ptr as *const T
 }
 
+static CSTRING_CACHE: Lazy<Mutex<HashMap<Box<str>, &'static CStr>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns a NUL-terminated version of `s` that stays valid until the end of the process.
+///
+/// The result is cached, so calling this repeatedly with the same string, e.g. the name of a
+/// property or signal, only allocates the first time. Strings that are already NUL-terminated
+/// are returned without any allocation.
+///
+/// # Panics
+///
+/// Panics if `s` contains NUL bytes anywhere but at its end.
+pub fn static_cstring(s: &'static str) -> &'static CStr {
+    if s.ends_with('\0') {
+        return CStr::from_bytes_with_nul(s.as_bytes()).expect("String contains NUL bytes");
+    }
+
+    cached_cstring(s)
+}
+
+// Returns a NUL-terminated version of `s` from the cache of `static_cstring`, adding it to the
+// cache if necessary.
+//
+// As cached strings are never freed this must only be used if `s` is known to be from a bounded
+// set, e.g. because it is the name of an existing property or signal.
+pub(crate) fn cached_cstring(s: &str) -> &'static CStr {
+    let mut cache = CSTRING_CACHE.lock().unwrap();
+    if let Some(cstr) = cache.get(s) {
+        return cstr;
+    }
+
+    let cstr: &'static CStr = Box::leak(
+        CString::new(s)
+            .expect("String contains NUL bytes")
+            .into_boxed_c_str(),
+    );
+    cache.insert(s.into(), cstr);
+    cstr
+}
+
+// Calls `func` with a NUL-terminated version of `s`, taken from the cache of `static_cstring` if
+// possible.
+//
+// The string is only added to the cache if `func` returns `Some`, e.g. because `s` was found to
+// be the name of an existing property or signal.
+pub(crate) fn with_cached_cstring<R, F: FnOnce(&CStr) -> Option<R>>(s: &str, func: F) -> Option<R> {
+    let cached = CSTRING_CACHE.lock().unwrap().get(s).copied();
+    if let Some(cstr) = cached {
+        return func(cstr);
+    }
+
+    let res = func(&CString::new(s).expect("String contains NUL bytes"))?;
+    cached_cstring(s);
+    Some(res)
+}
+
 /// A trait for creating an uninitialized value. Handy for receiving outparams.
 pub trait Uninitialized {
     /// Returns an uninitialized value.
@@ -450,6 +531,49 @@ impl GlibPtrDefault for String {
     type GlibType = *mut c_char;
 }
 
+// Owned or shared string types that are converted like the `str` they dereference to
+macro_rules! str_like_to_glib_ptr {
+    ([$($generics:tt)*] $name:ty) => {
+        impl<'a, $($generics)*> ToGlibPtr<'a, *const c_char> for $name {
+            type Storage = CString;
+
+            #[inline]
+            fn to_glib_none(&'a self) -> Stash<'a, *const c_char, Self> {
+                let s = ToGlibPtr::<*const c_char>::to_glib_none(&**self);
+                Stash(s.0, s.1)
+            }
+
+            #[inline]
+            fn to_glib_full(&self) -> *const c_char {
+                ToGlibPtr::<*const c_char>::to_glib_full(&**self)
+            }
+        }
+
+        impl<'a, $($generics)*> ToGlibPtr<'a, *mut c_char> for $name {
+            type Storage = CString;
+
+            #[inline]
+            fn to_glib_none(&'a self) -> Stash<'a, *mut c_char, Self> {
+                let s = ToGlibPtr::<*mut c_char>::to_glib_none(&**self);
+                Stash(s.0, s.1)
+            }
+
+            #[inline]
+            fn to_glib_full(&self) -> *mut c_char {
+                ToGlibPtr::<*mut c_char>::to_glib_full(&**self)
+            }
+        }
+
+        impl<$($generics)*> GlibPtrDefault for $name {
+            type GlibType = *mut c_char;
+        }
+    };
+}
+
+str_like_to_glib_ptr!(['b] Cow<'b, str>);
+str_like_to_glib_ptr!([] Box<str>);
+str_like_to_glib_ptr!([] Arc<str>);
+
 #[cfg(not(windows))]
 fn path_to_c(path: &Path) -> CString {
     // GLib paths on UNIX are always in the local encoding, just like in Rust
@@ -2001,11 +2125,13 @@ impl FromGlibPtrContainer<*const c_char, *mut glib_sys::GHashTable> for HashMap<
             value: glib_sys::gpointer,
             hash_map: glib_sys::gpointer,
         ) {
-            let key: String = from_glib_none(key as *const c_char);
-            let value: String = from_glib_none(value as *const c_char);
-            let hash_map: &mut HashMap<String, String> =
-                &mut *(hash_map as *mut HashMap<String, String>);
-            hash_map.insert(key, value);
+            ::guard_ffi(|| {
+                let key: String = from_glib_none(key as *const c_char);
+                let value: String = from_glib_none(value as *const c_char);
+                let hash_map: &mut HashMap<String, String> =
+                    &mut *(hash_map as *mut HashMap<String, String>);
+                hash_map.insert(key, value);
+            })
         }
         let mut map = HashMap::new();
         glib_sys::g_hash_table_foreach(
@@ -2141,6 +2267,136 @@ where
     }
 }
 
+/// An iterator over a borrowed `GList` that converts the elements to `T` on demand.
+///
+/// This is useful for `transfer none` lists returned by C functions if only some of the elements
+/// are needed, as it avoids converting all of them into a `Vec` first. `NULL` elements are
+/// skipped.
+pub struct GListIter<'a, T> {
+    ptr: *const glib_sys::GList,
+    phantom: PhantomData<(&'a glib_sys::GList, fn() -> T)>,
+}
+
+impl<'a, T> GListIter<'a, T> {
+    /// Creates an iterator over the list starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be `NULL` or point to a valid `GList`, and the list and its elements must stay
+    /// valid and unmodified for `'a`.
+    pub unsafe fn new(ptr: *const glib_sys::GList) -> Self {
+        GListIter {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Clone for GListIter<'a, T> {
+    fn clone(&self) -> Self {
+        GListIter {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for GListIter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GListIter").field("ptr", &self.ptr).finish()
+    }
+}
+
+impl<'a, T> Iterator for GListIter<'a, T>
+where
+    T: GlibPtrDefault + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while !self.ptr.is_null() {
+                let item_ptr: <T as GlibPtrDefault>::GlibType = Ptr::from((*self.ptr).data);
+                self.ptr = (*self.ptr).next;
+                if !item_ptr.is_null() {
+                    return Some(from_glib_none(item_ptr));
+                }
+            }
+            None
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for GListIter<'a, T> where
+    T: GlibPtrDefault + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+{
+}
+
+/// An iterator over a borrowed `GSList` that converts the elements to `T` on demand.
+///
+/// See [`GListIter`](struct.GListIter.html) for details.
+pub struct GSListIter<'a, T> {
+    ptr: *const glib_sys::GSList,
+    phantom: PhantomData<(&'a glib_sys::GSList, fn() -> T)>,
+}
+
+impl<'a, T> GSListIter<'a, T> {
+    /// Creates an iterator over the list starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be `NULL` or point to a valid `GSList`, and the list and its elements must stay
+    /// valid and unmodified for `'a`.
+    pub unsafe fn new(ptr: *const glib_sys::GSList) -> Self {
+        GSListIter {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Clone for GSListIter<'a, T> {
+    fn clone(&self) -> Self {
+        GSListIter {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for GSListIter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GSListIter")
+            .field("ptr", &self.ptr)
+            .finish()
+    }
+}
+
+impl<'a, T> Iterator for GSListIter<'a, T>
+where
+    T: GlibPtrDefault + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while !self.ptr.is_null() {
+                let item_ptr: <T as GlibPtrDefault>::GlibType = Ptr::from((*self.ptr).data);
+                self.ptr = (*self.ptr).next;
+                if !item_ptr.is_null() {
+                    return Some(from_glib_none(item_ptr));
+                }
+            }
+            None
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for GSListIter<'a, T> where
+    T: GlibPtrDefault + FromGlibPtrNone<<T as GlibPtrDefault>::GlibType>
+{
+}
+
 #[cfg(test)]
 mod tests {
     extern crate tempfile;
@@ -2152,6 +2408,21 @@ mod tests {
     use gstring::GString;
     use std::collections::HashMap;
 
+    #[test]
+    fn str_like() {
+        let s: Cow<str> = Cow::Borrowed("foo");
+        let stash: Stash<*const c_char, _> = s.to_glib_none();
+        assert_eq!(unsafe { CStr::from_ptr(stash.0) }.to_str().unwrap(), "foo");
+
+        let s = Box::<str>::from("bar");
+        let ptr: *mut c_char = s.to_glib_full();
+        assert_eq!(unsafe { String::from_glib_full(ptr) }, "bar");
+
+        let s = Arc::<str>::from("baz");
+        let stash: Stash<*mut c_char, _> = s.to_glib_none();
+        assert_eq!(unsafe { CStr::from_ptr(stash.0) }.to_str().unwrap(), "baz");
+    }
+
     #[test]
     fn string_hash_map() {
         let mut map = HashMap::new();
@@ -2196,6 +2467,42 @@ mod tests {
         assert_eq!(&v, strings);
     }
 
+    #[test]
+    fn static_cstring_cache() {
+        let a = static_cstring("foo-bar");
+        assert_eq!(a.to_str(), Ok("foo-bar"));
+        assert_eq!(a.as_ptr(), static_cstring("foo-bar").as_ptr());
+        assert_eq!(static_cstring("baz\0").to_str(), Ok("baz"));
+
+        assert_eq!(
+            with_cached_cstring("foo-bar", |s| Some(s.as_ptr())),
+            Some(a.as_ptr())
+        );
+        assert_eq!(with_cached_cstring("not-cached", |_| None::<()>), None);
+        assert!(!CSTRING_CACHE.lock().unwrap().contains_key("not-cached"));
+    }
+
+    #[test]
+    fn list_iter() {
+        let strings = &["A", "B", "C"];
+        let (ptr, _stash) =
+            ToGlibContainerFromSlice::<*mut glib_sys::GList>::to_glib_none_from_slice(strings);
+        let mut iter = unsafe { GListIter::<GString>::new(ptr) };
+        assert_eq!(iter.next().as_ref().map(GString::as_str), Some("A"));
+        assert_eq!(iter.clone().count(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), &["B", "C"]);
+
+        let refs = strings.iter().collect::<Vec<_>>();
+        let (ptr, _stash) =
+            ToGlibContainerFromSlice::<*mut glib_sys::GSList>::to_glib_none_from_slice(&refs[..]);
+        let v = unsafe { GSListIter::<GString>::new(ptr) }.collect::<Vec<_>>();
+        assert_eq!(v, strings);
+        assert_eq!(
+            unsafe { GSListIter::<GString>::new(ptr::null()) }.next(),
+            None
+        );
+    }
+
     #[test]
     #[cfg(not(target_os = "macos"))]
     fn test_paths() {
@@ -6,15 +6,18 @@
 
 use glib_sys;
 use gobject_sys;
+use libc::c_void;
 use translate::{
     from_glib, from_glib_none, FromGlib, FromGlibContainerAsVec, ToGlib, ToGlibContainerFromSlice,
     ToGlibPtr, ToGlibPtrMut,
 };
 use value::{FromValue, FromValueOptional, SetValue, Value};
 
+use std::borrow::Cow;
 use std::fmt;
 use std::mem;
 use std::ptr;
+use std::sync::Arc;
 
 /// A GLib or GLib-based library type
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -196,7 +199,7 @@ impl<'a, T: ?Sized + StaticType> StaticType for &'a mut T {
 }
 
 macro_rules! builtin {
-    ($name:ident, $val:ident) => {
+    ($name:ty, $val:ident) => {
         impl StaticType for $name {
             fn static_type() -> Type {
                 Type::$val
@@ -216,6 +219,16 @@ builtin!(f32, F32);
 builtin!(f64, F64);
 builtin!(str, String);
 builtin!(String, String);
+builtin!(Box<str>, String);
+builtin!(Arc<str>, String);
+builtin!(*mut c_void, Pointer);
+builtin!(ptr::NonNull<c_void>, Pointer);
+
+impl<'a> StaticType for Cow<'a, str> {
+    fn static_type() -> Type {
+        Type::String
+    }
+}
 
 impl<'a> StaticType for [&'a str] {
     fn static_type() -> Type {
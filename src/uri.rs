@@ -0,0 +1,58 @@
+// Copyright 2020, The Gtk-rs Project Developers.
+// See the COPYRIGHT file at the top-level directory of this distribution.
+// Licensed under the MIT license, see the LICENSE file or <https://opensource.org/licenses/MIT>
+
+/// Reserved characters that are allowed in a path, including `/`.
+///
+/// This and the other `URI_RESERVED_CHARS_*` constants are meant to be passed as
+/// `reserved_chars_allowed` to [`Uri::escape_string`]. The reverse is done by
+/// [`Uri::unescape_string`].
+///
+/// ```
+/// use glib::{Uri, URI_RESERVED_CHARS_ALLOWED_IN_PATH};
+///
+/// assert_eq!(
+///     Uri::escape_string("/a dir/ü?.txt", Some(URI_RESERVED_CHARS_ALLOWED_IN_PATH), false),
+///     "/a%20dir/%C3%BC%3F.txt"
+/// );
+/// ```
+///
+/// [`Uri::escape_string`]: struct.Uri.html#method.escape_string
+/// [`Uri::unescape_string`]: struct.Uri.html#method.unescape_string
+pub const URI_RESERVED_CHARS_ALLOWED_IN_PATH: &str = "!$&'()*+,;=:@/";
+
+/// Reserved characters that are allowed in a single path element.
+pub const URI_RESERVED_CHARS_ALLOWED_IN_PATH_ELEMENT: &str = "!$&'()*+,;=:@";
+
+/// Reserved characters that are allowed in the userinfo part of a URI.
+pub const URI_RESERVED_CHARS_ALLOWED_IN_USERINFO: &str = "!$&'()*+,;=:";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Uri;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            Uri::escape_string("a b/c:d", Some(URI_RESERVED_CHARS_ALLOWED_IN_PATH), false),
+            "a%20b/c:d"
+        );
+        assert_eq!(
+            Uri::escape_string(
+                "a b/c:d",
+                Some(URI_RESERVED_CHARS_ALLOWED_IN_PATH_ELEMENT),
+                false
+            ),
+            "a%20b%2Fc:d"
+        );
+        assert_eq!(
+            Uri::escape_string(
+                "a b@c:d",
+                Some(URI_RESERVED_CHARS_ALLOWED_IN_USERINFO),
+                false
+            ),
+            "a%20b%40c:d"
+        );
+    }
+}
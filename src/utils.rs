@@ -11,6 +11,7 @@ use std::path::{Path, PathBuf};
 use std::ptr;
 use translate::*;
 use Error;
+use MainContext;
 
 /// Same as [`get_prgname()`].
 ///
@@ -34,6 +35,38 @@ pub fn set_prgname(name: Option<&str>) {
     unsafe { glib_sys::g_set_prgname(name.to_glib_none().0) }
 }
 
+/// Sets up GLib for running tests on the current thread.
+///
+/// This consolidates the setup usually needed by test suites using GLib:
+///
+/// * The program name is set to the name of the test executable, unless one was set already.
+/// * Warnings and critical warnings are made non-fatal, even if `G_DEBUG=fatal-warnings` is set.
+/// * A new [`MainContext`] is created and pushed as thread default main context of the current
+///   thread. It stays the thread default main context until it is popped with
+///   [`MainContext::pop_thread_default()`].
+///
+/// Returns the new main context.
+///
+/// [`MainContext`]: struct.MainContext.html
+/// [`MainContext::pop_thread_default()`]: struct.MainContext.html#method.pop_thread_default
+pub fn init_for_tests() -> MainContext {
+    if get_prgname().is_none() {
+        let name = std::env::current_exe().ok().and_then(|exe| {
+            exe.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+        if let Some(name) = name {
+            set_prgname(Some(&name));
+        }
+    }
+
+    ::log_set_always_fatal(::LogLevels::LEVEL_ERROR);
+
+    let context = MainContext::new();
+    context.push_thread_default();
+    context
+}
+
 pub fn getenv<K: AsRef<OsStr>>(variable_name: K) -> Option<OsString> {
     #[cfg(not(windows))]
     use glib_sys::g_getenv;
@@ -213,6 +246,14 @@ mod tests {
         assert_eq!(env::var_os(VAR_NAME), Some(val.into()));
     }
 
+    #[test]
+    fn init_for_tests() {
+        let context = ::init_for_tests();
+        assert!(::get_prgname().is_some());
+        assert_eq!(::MainContext::ref_thread_default(), context);
+        context.pop_thread_default();
+    }
+
     #[test]
     fn getenv() {
         check_getenv("Test");
@@ -80,20 +80,25 @@
 //! ```
 
 use libc::{c_char, c_void};
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::error;
 use std::ffi::CStr;
 use std::fmt;
+use std::iter;
 use std::marker::PhantomData;
 use std::mem;
 use std::ops::Deref;
 use std::ptr;
+use std::slice;
+use std::sync::Arc;
+use std::vec;
 
 use glib_sys;
 use gobject_sys;
 use gstring::GString;
 use translate::*;
 use types::{StaticType, Type};
+use variant::{ToVariant, Variant};
 
 /// An error returned from the [`get`](struct.Value.html#method.get)
 /// or [`get_some`](struct.Value.html#method.get_some) functions on a [`Value`](struct.Value.html)
@@ -144,12 +149,21 @@ impl Value {
                 gobject_sys::g_type_check_is_value_type(type_.to_glib()),
                 glib_sys::GTRUE
             );
-            let mut value = Value::uninitialized();
+            let mut value = Value::new_empty();
             gobject_sys::g_value_init(value.to_glib_none_mut().0, type_.to_glib());
             value
         }
     }
 
+    /// Creates a new empty `Value` that is not initialized with any type.
+    ///
+    /// This corresponds to `G_VALUE_INIT` in C. The value is of type `Type::Invalid` and can't
+    /// store anything until it is initialized, which is usually done by C functions that return
+    /// values via a `GValue` out parameter.
+    pub fn new_empty() -> Self {
+        unsafe { Value(mem::MaybeUninit::zeroed().assume_init()) }
+    }
+
     /// Tries to downcast to a `TypedValue`.
     ///
     /// Returns `Ok(TypedValue<T>)` if the value carries a type corresponding
@@ -262,6 +276,56 @@ impl Value {
         }
     }
 
+    /// Converts the contents of `variant` into a `Value` of the corresponding type.
+    ///
+    /// Basic variant types are converted to the fundamental type that can hold them, e.g. `i`
+    /// into `i32`, `n` into `i32` and `s`, `o` and `g` into `String`. `as` is converted into a
+    /// string vector. All other variants are stored as is in a `Variant` value.
+    pub fn from_variant(variant: &Variant) -> Self {
+        match variant.type_().to_str() {
+            "b" => variant.get::<bool>().unwrap().to_value(),
+            "y" => variant.get::<u8>().unwrap().to_value(),
+            "n" => i32::from(variant.get::<i16>().unwrap()).to_value(),
+            "q" => u32::from(variant.get::<u16>().unwrap()).to_value(),
+            "i" => variant.get::<i32>().unwrap().to_value(),
+            "u" => variant.get::<u32>().unwrap().to_value(),
+            "x" => variant.get::<i64>().unwrap().to_value(),
+            "t" => variant.get::<u64>().unwrap().to_value(),
+            "d" => variant.get::<f64>().unwrap().to_value(),
+            "s" | "o" | "g" => variant.get_str().unwrap().to_value(),
+            "as" => variant.get::<Vec<String>>().unwrap().to_value(),
+            _ => variant.to_value(),
+        }
+    }
+
+    /// Converts the value into a `Variant` if its type has a corresponding variant type.
+    ///
+    /// This is the inverse of [`from_variant`](#method.from_variant), with `i8` and `f32` being
+    /// converted to `y` and `d`. `Variant` values return the contained variant. `None` is
+    /// returned for all other types and for `None` strings.
+    pub fn to_variant(&self) -> Option<Variant> {
+        match self.type_() {
+            Type::Bool => self.get_some::<bool>().ok().map(|v| v.to_variant()),
+            Type::I8 => self.get_some::<i8>().ok().map(|v| (v as u8).to_variant()),
+            Type::U8 => self.get_some::<u8>().ok().map(|v| v.to_variant()),
+            Type::I32 => self.get_some::<i32>().ok().map(|v| v.to_variant()),
+            Type::U32 => self.get_some::<u32>().ok().map(|v| v.to_variant()),
+            Type::I64 => self.get_some::<i64>().ok().map(|v| v.to_variant()),
+            Type::U64 => self.get_some::<u64>().ok().map(|v| v.to_variant()),
+            Type::F32 => self
+                .get_some::<f32>()
+                .ok()
+                .map(|v| f64::from(v).to_variant()),
+            Type::F64 => self.get_some::<f64>().ok().map(|v| v.to_variant()),
+            Type::String => self.get::<&str>().ok()?.map(|v| v.to_variant()),
+            Type::Variant => self.get::<Variant>().ok()?,
+            t if t == Vec::<String>::static_type() => {
+                self.get::<Vec<String>>().ok()?.map(|v| v.to_variant())
+            }
+            _ => None,
+        }
+    }
+
     #[doc(hidden)]
     pub fn into_raw(self) -> gobject_sys::GValue {
         unsafe {
@@ -336,7 +400,7 @@ impl From<SendValue> for Value {
 
 impl Uninitialized for Value {
     unsafe fn uninitialized() -> Value {
-        mem::zeroed()
+        Value::new_empty()
     }
 }
 
@@ -450,7 +514,7 @@ macro_rules! from_glib {
 
         impl FromGlibPtrFull<*mut gobject_sys::GValue> for $name {
             unsafe fn from_glib_full(ptr: *mut gobject_sys::GValue) -> Self {
-                let mut ret = Value::uninitialized();
+                let mut ret = Value::new_empty();
                 ptr::swap(&mut ret.0, ptr);
                 glib_sys::g_free(ptr as *mut c_void);
                 $wrap(ret)
@@ -863,6 +927,146 @@ impl<T: ?Sized + SetValue + Send + ToValue> ToSendValue for T {
     }
 }
 
+/// A vector of [`SendValue`](struct.SendValue.html)s, e.g. for transferring a batch of values
+/// between threads at once.
+///
+/// It can be created from an iterator over any type implementing `ToSendValue` and be drained
+/// again into values of a specific type with [`drain_as`](#method.drain_as).
+#[derive(Clone, Debug, Default)]
+pub struct SendValueVec(Vec<SendValue>);
+
+impl SendValueVec {
+    /// Creates a new, empty `SendValueVec`.
+    pub fn new() -> Self {
+        SendValueVec(Vec::new())
+    }
+
+    /// Creates a new, empty `SendValueVec` with space for at least `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SendValueVec(Vec::with_capacity(capacity))
+    }
+
+    /// Appends a `SendValue` clone of `value`.
+    pub fn push<T: ToSendValue + ?Sized>(&mut self, value: &T) {
+        self.0.push(value.to_send_value());
+    }
+
+    /// Moves all values of `other` to the end of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut SendValueVec) {
+        self.0.append(&mut other.0);
+    }
+
+    /// Returns the contained values as a slice of `Value`s.
+    pub fn as_values(&self) -> &[Value] {
+        // This cast is safe because SendValue is a transparent wrapper around Value
+        unsafe { &*(self.0.as_slice() as *const [SendValue] as *const [Value]) }
+    }
+
+    /// Removes all values and returns an iterator that yields them converted to `T`.
+    ///
+    /// Each item is the result of [`Value::get`](struct.Value.html#method.get) on the
+    /// corresponding value. All values are removed, even if the iterator is not fully consumed.
+    pub fn drain_as<T>(&mut self) -> DrainAs<T>
+    where
+        T: for<'a> FromValueOptional<'a>,
+    {
+        DrainAs(self.0.drain(..), PhantomData)
+    }
+
+    /// Returns the contained `Vec`.
+    pub fn into_inner(self) -> Vec<SendValue> {
+        self.0
+    }
+}
+
+impl Deref for SendValueVec {
+    type Target = [SendValue];
+
+    fn deref(&self) -> &[SendValue] {
+        &self.0
+    }
+}
+
+impl From<Vec<SendValue>> for SendValueVec {
+    fn from(values: Vec<SendValue>) -> Self {
+        SendValueVec(values)
+    }
+}
+
+impl From<SendValueVec> for Vec<SendValue> {
+    fn from(values: SendValueVec) -> Self {
+        values.0
+    }
+}
+
+impl<T: ToSendValue> iter::FromIterator<T> for SendValueVec {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SendValueVec(iter.into_iter().map(|v| v.to_send_value()).collect())
+    }
+}
+
+impl<T: ToSendValue> Extend<T> for SendValueVec {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|v| v.to_send_value()));
+    }
+}
+
+impl IntoIterator for SendValueVec {
+    type Item = SendValue;
+    type IntoIter = vec::IntoIter<SendValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SendValueVec {
+    type Item = &'a SendValue;
+    type IntoIter = slice::Iter<'a, SendValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A draining iterator over a [`SendValueVec`](struct.SendValueVec.html) that converts the
+/// values to `T`.
+///
+/// Created by [`SendValueVec::drain_as`](struct.SendValueVec.html#method.drain_as).
+pub struct DrainAs<'a, T>(vec::Drain<'a, SendValue>, PhantomData<T>);
+
+impl<'a, T> fmt::Debug for DrainAs<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("DrainAs").field(&self.0).finish()
+    }
+}
+
+impl<'a, T> Iterator for DrainAs<'a, T>
+where
+    T: for<'b> FromValueOptional<'b>,
+{
+    type Item = Result<Option<T>, GetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| v.get::<T>())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DrainAs<'a, T>
+where
+    T: for<'b> FromValueOptional<'b>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|v| v.get::<T>())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DrainAs<'a, T> where T: for<'b> FromValueOptional<'b> {}
+
 /// Extracts a value.
 ///
 /// Types that don't support a `None` value always return `Some`.
@@ -1013,6 +1217,27 @@ impl SetValueOptional for String {
     }
 }
 
+// Owned or shared string types that are stored like the `str` they dereference to
+macro_rules! str_like_set_value {
+    ([$($generics:tt)*] $name:ty) => {
+        impl<$($generics)*> SetValue for $name {
+            unsafe fn set_value(value: &mut Value, this: &Self) {
+                SetValue::set_value(value, &**this)
+            }
+        }
+
+        impl<$($generics)*> SetValueOptional for $name {
+            unsafe fn set_value_optional(value: &mut Value, this: Option<&Self>) {
+                SetValueOptional::set_value_optional(value, this.map(|s| &**s))
+            }
+        }
+    };
+}
+
+str_like_set_value!(['b] Cow<'b, str>);
+str_like_set_value!([] Box<str>);
+str_like_set_value!([] Arc<str>);
+
 impl<'a> FromValueOptional<'a> for bool {
     unsafe fn from_value_optional(value: &'a Value) -> Option<Self> {
         Some(from_glib(gobject_sys::g_value_get_boolean(
@@ -1064,6 +1289,45 @@ numeric!(u64, g_value_get_uint64, g_value_set_uint64);
 numeric!(f32, g_value_get_float, g_value_set_float);
 numeric!(f64, g_value_get_double, g_value_set_double);
 
+impl<'a> FromValueOptional<'a> for *mut c_void {
+    unsafe fn from_value_optional(value: &'a Value) -> Option<Self> {
+        Some(gobject_sys::g_value_get_pointer(value.to_glib_none().0))
+    }
+}
+
+impl<'a> FromValue<'a> for *mut c_void {
+    unsafe fn from_value(value: &'a Value) -> Self {
+        gobject_sys::g_value_get_pointer(value.to_glib_none().0)
+    }
+}
+
+impl SetValue for *mut c_void {
+    unsafe fn set_value(value: &mut Value, this: &Self) {
+        gobject_sys::g_value_set_pointer(value.to_glib_none_mut().0, *this)
+    }
+}
+
+impl<'a> FromValueOptional<'a> for ptr::NonNull<c_void> {
+    unsafe fn from_value_optional(value: &'a Value) -> Option<Self> {
+        ptr::NonNull::new(gobject_sys::g_value_get_pointer(value.to_glib_none().0))
+    }
+}
+
+impl SetValue for ptr::NonNull<c_void> {
+    unsafe fn set_value(value: &mut Value, this: &Self) {
+        gobject_sys::g_value_set_pointer(value.to_glib_none_mut().0, this.as_ptr())
+    }
+}
+
+impl SetValueOptional for ptr::NonNull<c_void> {
+    unsafe fn set_value_optional(value: &mut Value, this: Option<&Self>) {
+        gobject_sys::g_value_set_pointer(
+            value.to_glib_none_mut().0,
+            this.map_or(ptr::null_mut(), |this| this.as_ptr()),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1078,6 +1342,32 @@ mod tests {
         thread::spawn(move || drop(v)).join().unwrap();
     }
 
+    #[test]
+    fn test_new_empty() {
+        let v = Value::new_empty();
+        assert_eq!(v.type_(), Type::Invalid);
+        assert!(v.get::<i32>().is_err());
+    }
+
+    #[test]
+    fn test_str_like() {
+        let v = Cow::Borrowed("foo").to_value();
+        assert_eq!(v.get::<&str>(), Ok(Some("foo")));
+
+        let v = Cow::<str>::Owned(String::from("bar")).to_value();
+        assert_eq!(v.get::<&str>(), Ok(Some("bar")));
+
+        let v = Box::<str>::from("baz").to_value();
+        assert_eq!(v.get::<&str>(), Ok(Some("baz")));
+
+        let v = Arc::<str>::from("qux").to_value();
+        assert_eq!(v.get::<&str>(), Ok(Some("qux")));
+
+        let v = None::<Arc<str>>.to_value();
+        assert_eq!(v.type_(), Type::String);
+        assert_eq!(v.get::<&str>(), Ok(None));
+    }
+
     #[test]
     fn test_strv() {
         let v = vec!["123", "456"].to_value();
@@ -1091,6 +1381,20 @@ mod tests {
             v.get::<Vec<GString>>(),
             Ok(Some(vec![GString::from("123"), GString::from("456")]))
         );
+
+        let strv = vec![GString::from("123"), GString::from("456")];
+        let v = strv.to_value();
+        assert_eq!(v.type_(), Vec::<GString>::static_type());
+        assert_eq!(v.get::<Vec<GString>>(), Ok(Some(strv.clone())));
+
+        let v = strv[..].to_value();
+        assert_eq!(
+            v.get::<Vec<String>>(),
+            Ok(Some(vec![String::from("123"), String::from("456")]))
+        );
+        assert_eq!(v.to_variant(), Some(strv.to_variant()));
+        assert_eq!(strv[..].to_variant(), strv.to_variant());
+        assert_eq!(strv.to_variant().get::<Vec<GString>>(), Some(strv));
     }
 
     #[test]
@@ -1124,6 +1428,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_variant() {
+        let v = Value::from_variant(&42i16.to_variant());
+        assert_eq!(v.type_(), Type::I32);
+        assert_eq!(v.get_some::<i32>(), Ok(42));
+        assert_eq!(v.to_variant(), Some(42i32.to_variant()));
+
+        let v = Value::from_variant(&"foo".to_variant());
+        assert_eq!(v.get::<&str>(), Ok(Some("foo")));
+        assert_eq!(v.to_variant(), Some("foo".to_variant()));
+
+        let strv = vec![String::from("a"), String::from("b")];
+        let v = Value::from_variant(&strv.to_variant());
+        assert_eq!(v.get::<Vec<String>>(), Ok(Some(strv.clone())));
+        assert_eq!(v.to_variant(), Some(strv.to_variant()));
+
+        let variant = (1u8, true).to_variant();
+        let v = Value::from_variant(&variant);
+        assert_eq!(v.type_(), Type::Variant);
+        assert_eq!(v.to_variant(), Some(variant));
+
+        assert_eq!(None::<&str>.to_value().to_variant(), None);
+        assert_eq!(1.5f32.to_value().to_variant(), Some(1.5f64.to_variant()));
+    }
+
+    #[test]
+    fn test_pointer() {
+        let mut data = 42u32;
+        let ptr = &mut data as *mut u32 as *mut c_void;
+
+        let v = ptr.to_value();
+        assert_eq!(v.type_(), Type::Pointer);
+        assert_eq!(v.get_some::<*mut c_void>(), Ok(ptr));
+        assert_eq!(v.get::<ptr::NonNull<c_void>>(), Ok(ptr::NonNull::new(ptr)));
+
+        let v = ptr::NonNull::new(ptr).to_value();
+        assert_eq!(v.type_(), Type::Pointer);
+        assert_eq!(v.get_some::<*mut c_void>(), Ok(ptr));
+
+        let v = None::<ptr::NonNull<c_void>>.to_value();
+        assert_eq!(v.get_some::<*mut c_void>(), Ok(ptr::null_mut()));
+        assert_eq!(v.get::<ptr::NonNull<c_void>>(), Ok(None));
+    }
+
     #[test]
     fn test_transform() {
         let v = 123.to_value();
@@ -1132,4 +1480,30 @@ mod tests {
             .expect("Failed to transform to string");
         assert_eq!(v2.get::<&str>(), Ok(Some("123")));
     }
+
+    #[test]
+    fn test_send_value_vec() {
+        use std::thread;
+
+        let mut values = (1..=3).collect::<SendValueVec>();
+        values.push(&4);
+        assert_eq!(values.len(), 4);
+        assert_eq!(values.as_values()[3].get_some::<i32>(), Ok(4));
+
+        let values = thread::spawn(move || values).join().unwrap();
+        let mut values = SendValueVec::from(values.into_inner());
+        assert_eq!(
+            values
+                .drain_as::<i32>()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2), Some(3), Some(4)]
+        );
+        assert!(values.is_empty());
+
+        let mut values = vec!["a", "b"].into_iter().collect::<SendValueVec>();
+        let mut iter = values.drain_as::<u32>();
+        assert_eq!(iter.len(), 2);
+        assert!(iter.next().unwrap().is_err());
+    }
 }
@@ -68,16 +68,18 @@ impl ValueArray {
             b: glib_sys::gconstpointer,
             func: glib_sys::gpointer,
         ) -> i32 {
-            let func = func as *mut &mut (dyn FnMut(&Value, &Value) -> Ordering);
-
-            let a = &*(a as *const Value);
-            let b = &*(b as *const Value);
-
-            match (*func)(&a, &b) {
-                Ordering::Less => -1,
-                Ordering::Equal => 0,
-                Ordering::Greater => 1,
-            }
+            ::guard_ffi(|| {
+                let func = func as *mut &mut (dyn FnMut(&Value, &Value) -> Ordering);
+
+                let a = &*(a as *const Value);
+                let b = &*(b as *const Value);
+
+                match (*func)(&a, &b) {
+                    Ordering::Less => -1,
+                    Ordering::Equal => 0,
+                    Ordering::Greater => 1,
+                }
+            })
         }
         unsafe {
             let mut func = compare_func;
@@ -92,6 +92,7 @@ use gstring::GString;
 use std::borrow::Cow;
 use std::cmp::{Eq, Ordering, PartialEq, PartialOrd};
 use std::collections::HashMap;
+use std::error;
 use std::fmt;
 use std::hash::{BuildHasher, Hash, Hasher};
 use std::slice;
@@ -204,6 +205,84 @@ impl Variant {
         }
     }
 
+    /// Tries to read a child item out of a container `Variant` instance and extract a value of
+    /// type `T` from it.
+    ///
+    /// Returns `Ok(None)` if `self` is not a container or `index` is out of bounds, and an error
+    /// if the child's type doesn't match `T`. Unlike [`get_child_value`] this never panics, which
+    /// makes it suitable for untrusted data.
+    ///
+    /// [`get_child_value`]: #method.get_child_value
+    pub fn try_child_get<T: FromVariant>(
+        &self,
+        index: usize,
+    ) -> Result<Option<T>, VariantTypeMismatchError> {
+        if !self.is_container() || index >= self.n_children() {
+            return Ok(None);
+        }
+
+        let child = self.get_child_value(index);
+        child.try_get().map(Some)
+    }
+
+    /// Tries to extract a value of type `T`, returning an error describing the expected and
+    /// actual types if they don't match.
+    pub fn try_get<T: FromVariant>(&self) -> Result<T, VariantTypeMismatchError> {
+        T::from_variant(self).ok_or_else(|| {
+            VariantTypeMismatchError::new(
+                self.type_().to_owned(),
+                T::static_variant_type().into_owned(),
+            )
+        })
+    }
+
+    /// Looks up a value in a dictionary `Variant`.
+    ///
+    /// `self` must be a dictionary keyed by strings or object paths, e.g. of type `a{sv}`,
+    /// otherwise an error is returned. If `expected_type` is given the value is only returned if
+    /// it is of that type.
+    ///
+    /// Returns `Ok(None)` if there is no (matching) value for `key`.
+    pub fn lookup_value(
+        &self,
+        key: &str,
+        expected_type: Option<&VariantTy>,
+    ) -> Result<Option<Variant>, VariantTypeMismatchError> {
+        let type_ = self.type_().to_str();
+        if !type_.starts_with("a{s") && !type_.starts_with("a{o") {
+            return Err(VariantTypeMismatchError::new(
+                self.type_().to_owned(),
+                VariantType::new("a{s*}").unwrap(),
+            ));
+        }
+
+        unsafe {
+            Ok(from_glib_full(glib_sys::g_variant_lookup_value(
+                self.to_glib_none().0,
+                key.to_glib_none().0,
+                expected_type.to_glib_none().0,
+            )))
+        }
+    }
+
+    /// Looks up a value in a dictionary `Variant` and extracts a value of type `T` from it.
+    ///
+    /// Boxed values, e.g. the values of an `a{sv}` dictionary, are unboxed first. Returns an
+    /// error if `self` is not a dictionary or if the value's type doesn't match `T`.
+    pub fn lookup<T: FromVariant>(&self, key: &str) -> Result<Option<T>, VariantTypeMismatchError> {
+        let value = match self.lookup_value(key, None)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        if value.type_().to_str() == "v" && T::static_variant_type().to_str() != "v" {
+            let value = value.get_variant().unwrap();
+            value.try_get().map(Some)
+        } else {
+            value.try_get().map(Some)
+        }
+    }
+
     /// Tries to extract a `&str`.
     ///
     /// Returns `Some` if the variant has a string type (`s`, `o` or `g` type
@@ -326,8 +405,111 @@ impl Variant {
     pub fn is_container(&self) -> bool {
         unsafe { glib_sys::g_variant_is_container(self.to_glib_none().0) != glib_sys::GFALSE }
     }
+
+    /// Pretty-prints the value in the GVariant text format.
+    ///
+    /// If `type_annotate` is `true`, type annotations are added where needed to parse the
+    /// output back into a value of the same type.
+    pub fn print(&self, type_annotate: bool) -> GString {
+        unsafe {
+            from_glib_full(glib_sys::g_variant_print(
+                self.to_glib_none().0,
+                type_annotate.to_glib(),
+            ))
+        }
+    }
+
+    /// Pretty-prints the value over multiple lines, with every child of a non-empty container
+    /// on its own line and indented according to its nesting level.
+    ///
+    /// This is meant for reading large nested values, e.g. when debugging D-Bus messages. The
+    /// output is not guaranteed to be parseable back into a `Variant`.
+    pub fn print_indented(&self) -> String {
+        let mut out = String::new();
+        print_indented(self, 0, &mut out);
+        out
+    }
+}
+
+fn print_indented(variant: &Variant, level: usize, out: &mut String) {
+    const INDENT: &str = "  ";
+
+    let type_ = variant.type_().to_str();
+    let (open, close) = match type_.as_bytes()[0] {
+        b'a' if type_.as_bytes()[1] == b'{' => ("{", "}"),
+        b'a' => ("[", "]"),
+        b'(' => ("(", ")"),
+        b'{' => {
+            print_indented(&variant.get_child_value(0), level, out);
+            out.push_str(": ");
+            print_indented(&variant.get_child_value(1), level, out);
+            return;
+        }
+        b'v' => {
+            out.push('<');
+            print_indented(&variant.get_child_value(0), level, out);
+            out.push('>');
+            return;
+        }
+        b'm' if variant.n_children() == 1 => {
+            print_indented(&variant.get_child_value(0), level, out);
+            return;
+        }
+        _ => {
+            out.push_str(&variant.print(false));
+            return;
+        }
+    };
+
+    if variant.n_children() == 0 {
+        out.push_str(&variant.print(false));
+        return;
+    }
+
+    out.push_str(open);
+    out.push('\n');
+    for (i, child) in variant.iter().enumerate() {
+        if i > 0 {
+            out.push_str(",\n");
+        }
+        for _ in 0..=level {
+            out.push_str(INDENT);
+        }
+        print_indented(&child, level + 1, out);
+    }
+    out.push('\n');
+    for _ in 0..level {
+        out.push_str(INDENT);
+    }
+    out.push_str(close);
+}
+
+/// An error returned from the [`try_get`](struct.Variant.html#method.try_get) family of
+/// functions on a [`Variant`](struct.Variant.html) if the type of the value doesn't match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantTypeMismatchError {
+    pub actual: VariantType,
+    pub expected: VariantType,
 }
 
+impl VariantTypeMismatchError {
+    pub fn new(actual: VariantType, expected: VariantType) -> Self {
+        VariantTypeMismatchError { actual, expected }
+    }
+}
+
+impl fmt::Display for VariantTypeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type mismatch: Expected '{}' got '{}'",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl error::Error for VariantTypeMismatchError {}
+
 unsafe impl Send for Variant {}
 unsafe impl Sync for Variant {}
 
@@ -343,13 +525,7 @@ impl fmt::Debug for Variant {
 
 impl fmt::Display for Variant {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let serialized: GString = unsafe {
-            from_glib_full(glib_sys::g_variant_print(
-                self.to_glib_none().0,
-                false.to_glib(),
-            ))
-        };
-        f.write_str(&serialized)
+        f.write_str(&self.print(false))
     }
 }
 
@@ -514,6 +690,31 @@ impl FromVariant for String {
     }
 }
 
+impl StaticVariantType for GString {
+    fn static_variant_type() -> Cow<'static, VariantTy> {
+        unsafe { VariantTy::from_str_unchecked("s").into() }
+    }
+}
+
+impl ToVariant for GString {
+    fn to_variant(&self) -> Variant {
+        self.as_str().to_variant()
+    }
+}
+
+impl FromVariant for GString {
+    fn from_variant(variant: &Variant) -> Option<Self> {
+        variant.get_str().map(GString::from)
+    }
+}
+
+impl ToVariant for [GString] {
+    fn to_variant(&self) -> Variant {
+        let children: Vec<_> = self.iter().map(ToVariant::to_variant).collect();
+        Variant::array::<GString>(&children)
+    }
+}
+
 impl StaticVariantType for str {
     fn static_variant_type() -> Cow<'static, VariantTy> {
         unsafe { VariantTy::from_str_unchecked("s").into() }
@@ -916,6 +1117,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try_child_get() {
+        let v = (1u32, "foo").to_variant();
+        assert_eq!(v.try_child_get::<u32>(0), Ok(Some(1)));
+        assert_eq!(v.try_child_get::<String>(1), Ok(Some(String::from("foo"))));
+        assert_eq!(v.try_child_get::<u32>(2), Ok(None));
+
+        let err = v.try_child_get::<bool>(0).unwrap_err();
+        assert_eq!(err.actual, "u");
+        assert_eq!(err.expected, "b");
+
+        assert_eq!(1u32.to_variant().try_child_get::<u32>(0), Ok(None));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut map = HashMap::new();
+        map.insert("foo", 1u32.to_variant());
+        map.insert("bar", "bar".to_variant());
+        let v = map.to_variant();
+
+        assert_eq!(v.lookup::<u32>("foo"), Ok(Some(1)));
+        assert_eq!(v.lookup::<String>("bar"), Ok(Some(String::from("bar"))));
+        assert_eq!(v.lookup::<u32>("baz"), Ok(None));
+        assert!(v.lookup::<u32>("bar").is_err());
+
+        assert_eq!(
+            v.lookup_value("foo", Some(VariantTy::new("u").unwrap())),
+            Ok(Some(1u32.to_variant()))
+        );
+        assert_eq!(
+            v.lookup_value("foo", Some(VariantTy::new("s").unwrap())),
+            Ok(None)
+        );
+
+        let err = 1u32.to_variant().lookup_value("foo", None).unwrap_err();
+        assert_eq!(err.actual, "u");
+    }
+
+    #[test]
+    fn test_print() {
+        let v = (1u32, "foo").to_variant();
+        assert_eq!(v.print(false), "(1, 'foo')");
+        assert_eq!(v.print(true), "(uint32 1, 'foo')");
+    }
+
+    #[test]
+    fn test_print_indented() {
+        let mut map = HashMap::new();
+        map.insert("a", vec![1i32, 2]);
+        let v = (map, "x".to_variant(), Vec::<i32>::new()).to_variant();
+        assert_eq!(
+            v.print_indented(),
+            "(\n  {\n    'a': [\n      1,\n      2\n    ]\n  },\n  <'x'>,\n  []\n)"
+        );
+        assert_eq!(1u8.to_variant().print_indented(), "0x01");
+    }
+
     #[test]
     fn test_array() {
         // Test just the signature for now.
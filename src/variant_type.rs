@@ -37,6 +37,51 @@ impl VariantType {
     pub fn new(type_string: &str) -> Result<VariantType, ()> {
         VariantTy::new(type_string).map(ToOwned::to_owned)
     }
+
+    /// Creates a `VariantType` from a newly allocated `GVariantType`.
+    unsafe fn from_ptr_full(ptr: *mut glib_sys::GVariantType) -> VariantType {
+        assert!(!ptr.is_null());
+        VariantType {
+            ptr,
+            len: glib_sys::g_variant_type_get_string_length(ptr) as usize,
+        }
+    }
+
+    /// Creates an array type with elements of type `element`.
+    pub fn new_array(element: &VariantTy) -> VariantType {
+        unsafe { VariantType::from_ptr_full(glib_sys::g_variant_type_new_array(element.as_ptr())) }
+    }
+
+    /// Creates a maybe type containing `child`.
+    pub fn new_maybe(child: &VariantTy) -> VariantType {
+        unsafe { VariantType::from_ptr_full(glib_sys::g_variant_type_new_maybe(child.as_ptr())) }
+    }
+
+    /// Creates a tuple type with the given `items`.
+    pub fn new_tuple(items: &[&VariantTy]) -> VariantType {
+        let items: Vec<_> = items.iter().map(|item| item.as_ptr()).collect();
+        unsafe {
+            VariantType::from_ptr_full(glib_sys::g_variant_type_new_tuple(
+                items.as_ptr(),
+                items.len() as i32,
+            ))
+        }
+    }
+
+    /// Creates a dictionary entry type with the given `key` and `value` types.
+    ///
+    /// `key` has to be a basic type.
+    pub fn new_dict_entry(key: &VariantTy, value: &VariantTy) -> VariantType {
+        unsafe {
+            assert!(from_glib::<_, bool>(glib_sys::g_variant_type_is_basic(
+                key.as_ptr()
+            )));
+            VariantType::from_ptr_full(glib_sys::g_variant_type_new_dict_entry(
+                key.as_ptr(),
+                value.as_ptr(),
+            ))
+        }
+    }
 }
 
 unsafe impl Send for VariantType {}
@@ -449,4 +494,20 @@ mod tests {
 
         assert_eq!(VariantTy::static_type(), VariantTy::static_type());
     }
+
+    #[test]
+    fn builders() {
+        let s = VariantTy::new("s").unwrap();
+        let v = VariantTy::new("v").unwrap();
+        let i = VariantTy::new("i").unwrap();
+
+        assert_eq!(VariantType::new_array(s), "as");
+        assert_eq!(VariantType::new_maybe(i), "mi");
+        assert_eq!(VariantType::new_tuple(&[s, i, v]), "(siv)");
+        assert_eq!(VariantType::new_tuple(&[]), "()");
+
+        let entry = VariantType::new_dict_entry(s, v);
+        assert_eq!(entry, "{sv}");
+        assert_eq!(VariantType::new_array(&entry), "a{sv}");
+    }
 }
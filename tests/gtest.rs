@@ -0,0 +1,38 @@
+#[macro_use]
+extern crate glib;
+
+use glib::gtest;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn main() {
+    gtest::init();
+    assert!(gtest::is_initialized());
+
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let count_clone = count.clone();
+    gtest::add_func("/gtest/add-func", move || {
+        assert!(!gtest::is_subprocess());
+        count_clone.fetch_add(1, Ordering::SeqCst);
+    });
+
+    gtest::add_func("/gtest/message", || {
+        gtest::message("a diagnostic message");
+        assert!(!gtest::failed());
+    });
+
+    gtest::add_func("/gtest/expect-message", || {
+        gtest::expect_message(Some("gtest"), glib::LogLevel::Warning, "expected*");
+        g_warning!("gtest", "expected warning");
+        glib_test_assert_expected_messages!("gtest");
+    });
+
+    gtest::add_func("/gtest/skip", || {
+        gtest::skip(Some("skipped on purpose"));
+    });
+
+    let ret = gtest::run();
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+    std::process::exit(ret);
+}
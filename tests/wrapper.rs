@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate glib;
+
+use glib::gobject_sys;
+use glib::prelude::*;
+
+// Object wrappers have to be declarable from other crates, e.g. gio or gtk.
+glib_wrapper! {
+    pub struct MyObject(Object<gobject_sys::GObject, gobject_sys::GObjectClass, MyObjectClass>);
+
+    match fn {
+        get_type => || gobject_sys::g_object_get_type(),
+    }
+}
+
+#[test]
+fn wrapper_from_other_crate() {
+    let obj = glib::Object::new(MyObject::static_type(), &[])
+        .expect("Object::new failed")
+        .downcast::<MyObject>()
+        .expect("Not a MyObject");
+
+    assert!(obj.is::<glib::Object>());
+    let obj: &glib::Object = obj.upcast_ref();
+    assert!(obj.downcast_ref::<MyObject>().is_some());
+}